@@ -0,0 +1,95 @@
+use chess_template as lib;
+
+/// The search depth used by `go` if no depth is given.
+const DEFAULT_DEPTH: u32 = 2;
+
+/// A minimal engine speaking the Universal Chess Interface (UCI), such that the library can be plugged into GUIs like Arena or CuteChess.
+///
+/// Supports the commands `uci`, `isready`, `ucinewgame`, `position startpos|fen <fen> [moves <moves>]`, `go [depth <depth>]` and `quit`.
+/// Other commands are ignored, as the protocol prescribes.
+fn main() {
+    use std::io;
+    use std::io::prelude::*;
+
+    let mut game = lib::Game::new();
+
+    let input = io::stdin();
+    for line in input.lock().lines() {
+        let line = line.expect("Invalid iostream.");
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.first() {
+            Some(&"uci") => {
+                println!("id name chess_template");
+                println!("id author Viola Söderlund");
+                println!("uciok");
+            }
+            Some(&"isready") => println!("readyok"),
+            Some(&"ucinewgame") => game = lib::Game::new(),
+            Some(&"position") => match parse_position(&words[1..]) {
+                Ok(new_game) => game = new_game,
+                Err(message) => eprintln!("Error received: '{}'", message),
+            },
+            Some(&"go") => {
+                // the depth is given as `go depth <depth>`, other search limits are not supported
+                let depth = match words.iter().position(|word| *word == "depth") {
+                    Some(i) => words
+                        .get(i + 1)
+                        .and_then(|depth| depth.parse().ok())
+                        .unwrap_or(DEFAULT_DEPTH),
+                    None => DEFAULT_DEPTH,
+                };
+                println!("bestmove {}", best_move(&game, depth));
+            }
+            Some(&"quit") => break,
+            _ => (),
+        }
+
+        // make sure the GUI receives the reply right away
+        io::stdout().flush().expect("Invalid iostream.");
+    }
+}
+
+/// Sets up the game given by the arguments of the `position` command, e.g. `startpos moves e2e4 e7e5` or `fen <fen> moves e2e4`.
+fn parse_position(args: &[&str]) -> Result<lib::Game, lib::ChessError> {
+    let moves_start = args
+        .iter()
+        .position(|arg| *arg == "moves")
+        .unwrap_or(args.len());
+
+    let mut game = match args.first() {
+        Some(&"startpos") => lib::Game::new(),
+        Some(&"fen") => lib::Game::from_fen(&args[1..moves_start].join(" "))?,
+        _ => {
+            return Err(lib::ChessError::ParseError(String::from(
+                "The position should be given by 'startpos' or 'fen'.",
+            )))
+        }
+    };
+
+    for uci in args.iter().skip(moves_start + 1) {
+        game.make_move_uci(uci)?;
+    }
+    Ok(game)
+}
+
+/// Searches for the best move `depth` plies deep and returns it in UCI notation, or `0000` if the game has ended.
+fn best_move(game: &lib::Game, depth: u32) -> String {
+    let state = game.get_game_state();
+    if state != lib::GameState::InProgress && state != lib::GameState::Check {
+        return String::from("0000");
+    }
+
+    match game.evaluate_all_moves(depth).first() {
+        None => String::from("0000"),
+        Some(((from, to), _)) => {
+            // the search promotes pawns to queens
+            let mv = if game.is_promotion_move(*from, *to) {
+                lib::Move::new_promotion(*from, *to, lib::PieceType::Queen)
+            } else {
+                lib::Move::new(*from, *to)
+            };
+            mv.to_uci()
+        }
+    }
+}
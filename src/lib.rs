@@ -1,7 +1,10 @@
 // Author: Eskil Nyberg
 // Based on IndaPlus22/task-3/chess_template by Viola Söderlund, modified by Isak Larsson
 
+use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 /// Enum for the current state of the game.
 ///
@@ -12,20 +15,93 @@ use std::fmt;
 /// In this state, `get_possible_moves()` returns a limited list of moves.
 /// - `WaitingOnPromotionChoice` describes that the game is waiting for the user to choose which piece
 /// the recently moved pawn should be promoted to.
-/// - `GameOver` describes a finished game. All state-altering functions will not work in this state.
-/// This state is reached either by reaching a checkmate, stalemate or by a user-submitted defeat.
+/// - `GameOver` describes a game that has been won. All state-altering functions will not work in this state.
+/// This state is reached either by reaching a checkmate or by a user-submitted defeat.
+/// - `Draw` describes a game that has been drawn by a stalemate or by a draw rule such as the fifty-move rule. All state-altering functions will not work in this state.
+///
+/// Use `Game::game_result()` to find out how a finished game ended.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameState {
     InProgress,
     Check,
     WaitingOnPromotionChoice,
     GameOver,
+    Draw,
+}
+
+/// Enum for the result of a finished game, returned by `Game::game_result()`.
+///
+/// ### Results
+/// - `Checkmate(colour)` describes a game won by `colour` by checkmate.
+/// - `Resignation(colour)` describes a game won by `colour` since the opponent resigned.
+/// - `Timeout(colour)` describes a game won by `colour` since the opponent ran out of time, see `GameClock`.
+/// - `Stalemate` describes a game drawn since the active colour is not in check but cannot make a move.
+/// - `DrawByAgreement` describes a game drawn since a draw offer was accepted.
+/// - `DrawByRule(rule)` describes a game drawn by the rule `rule`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameResult {
+    Checkmate(Colour),
+    Resignation(Colour),
+    Timeout(Colour),
+    Stalemate,
+    DrawByAgreement,
+    DrawByRule(DrawRule),
+}
+
+/// Enum for the rules by which a game can be drawn. Is implemented by `GameResult`.
+///
+/// Contains the variants
+/// - `FiftyMoveRule`, for 50 moves by each player without a capture or a pawn move, when claimed with `Game::claim_fifty_move_draw`.
+/// - `SeventyFiveMoveRule`, for 75 moves by each player without a capture or a pawn move, which draws the game without a claim.
+/// - `ThreefoldRepetition`, for the same position occurring three times, when claimed with `Game::claim_threefold_repetition`.
+/// - `FivefoldRepetition`, for the same position occurring five times, which draws the game without a claim.
+/// - `InsufficientMaterial`, for neither colour having the material to checkmate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawRule {
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    InsufficientMaterial,
+}
+
+/// Enum for the errors returned by the fallible functions of the library, such that callers can handle them without matching on message text.
+///
+/// ### Errors
+/// - `OffBoard` describes a position outside of the board.
+/// - `NoPieceAtSource` describes a move from a square without a piece.
+/// - `WrongColour` describes a move of a piece that is not of the active colour.
+/// - `IllegalMove` describes a move that the piece cannot make, or that would put the own king in check.
+/// - `NotPlayable(state)` describes an action that cannot be performed in the current state `state` of the game, e.g. a move after the game has ended.
+/// - `InvalidPromotion(message)` describes a missing or invalid choice of piece to promote a pawn to.
+/// - `NothingToUndo` describes an undo before any move has been made.
+/// - `NoDrawOffer` describes accepting a draw when no draw has been offered.
+/// - `DrawNotClaimable` describes claiming a draw by a rule whose conditions are not met in the current position.
+/// - `ParseError(message)` describes input that could not be parsed, e.g. a malformed position, move or FEN.
+///
+/// Implements `Display`, giving a message that can be shown to the user, and `std::error::Error`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChessError {
+    OffBoard,
+    NoPieceAtSource,
+    WrongColour,
+    IllegalMove,
+    NotPlayable(GameState),
+    InvalidPromotion(String),
+    NothingToUndo,
+    NoDrawOffer,
+    DrawNotClaimable,
+    ParseError(String),
 }
 
 /// Enum for the colours of the board. Is implemented as an auxiliary state for by e.g. Piece and Game.
 ///
 /// Contains the variants `White` and `Black`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Colour {
     White,
     Black,
@@ -33,8 +109,15 @@ pub enum Colour {
 
 impl Colour {
     /// A function that returns the opposite colour
-    fn opposite(colour: Colour) -> Colour {
-        if colour == Colour::White {
+    ///
+    /// ```
+    /// use chess_template::Colour;
+    ///
+    /// assert_eq!(Colour::White.opposite(), Colour::Black);
+    /// assert_eq!(Colour::Black.opposite(), Colour::White);
+    /// ```
+    pub fn opposite(self) -> Colour {
+        if self == Colour::White {
             return Colour::Black;
         } else {
             return Colour::White;
@@ -46,6 +129,7 @@ impl Colour {
 ///
 /// Contains the variants `King`, `Queen`, `Rook`, `Knight`, `Bishop`, `Pawn`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PieceType {
     King,
     Queen,
@@ -55,7 +139,30 @@ pub enum PieceType {
     Pawn,
 }
 
+impl PieceType {
+    /// A function that returns the value of the piece type in pawns, used for comparative calculations such as material evaluation.
+    /// The king is given the value 0 since it can never be captured.
+    ///
+    /// ```
+    /// use chess_template::PieceType;
+    ///
+    /// assert_eq!(PieceType::Queen.value(), 9);
+    /// assert_eq!(PieceType::Knight.value(), PieceType::Bishop.value());
+    /// ```
+    pub fn value(&self) -> i32 {
+        match self {
+            PieceType::King => 0,
+            PieceType::Queen => 9,
+            PieceType::Rook => 5,
+            PieceType::Knight => 3,
+            PieceType::Bishop => 3,
+            PieceType::Pawn => 1,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Struct for some Piece.
 ///
 /// Is used in the engine as an Option<Piece>-structure implementing None where there are no pieces and Some(Piece) where there are pieces.
@@ -66,7 +173,105 @@ pub struct Piece {
     pub colour: Colour,
 }
 
+impl Piece {
+    /// Init-function for a piece of type `piece_type` and colour `colour`, e.g. for placing pieces with `Game::place_piece`.
+    ///
+    /// ```
+    /// use chess_template::{Colour, Piece, PieceType};
+    ///
+    /// let rook = Piece::new(PieceType::Rook, Colour::Black);
+    /// assert_eq!(rook.piece_type(), PieceType::Rook);
+    /// assert_eq!(rook.colour(), Colour::Black);
+    /// ```
+    pub fn new(piece_type: PieceType, colour: Colour) -> Piece {
+        Piece { piece_type, colour }
+    }
+
+    /// Returns the type of the piece.
+    ///
+    /// ```
+    /// use chess_template::{Game, PieceType, Position};
+    ///
+    /// let game = Game::new();
+    /// let e1 = Position::parse_str("e1").unwrap();
+    /// let piece = game.get_board()[e1.idx].unwrap();
+    /// assert_eq!(piece.piece_type(), PieceType::King);
+    /// ```
+    pub fn piece_type(&self) -> PieceType {
+        self.piece_type
+    }
+
+    /// Returns the colour of the piece.
+    ///
+    /// ```
+    /// use chess_template::{Colour, Game, Position};
+    ///
+    /// let game = Game::new();
+    /// let e1 = Position::parse_str("e1").unwrap();
+    /// let piece = game.get_board()[e1.idx].unwrap();
+    /// assert_eq!(piece.colour(), Colour::White);
+    /// ```
+    pub fn colour(&self) -> Colour {
+        self.colour
+    }
+
+    /// A function that parses a character representing a piece in FEN, uppercase for white and lowercase for black.
+    /// Returns `None` if the character does not represent a piece.
+    fn from_fen_char(c: char) -> Option<Piece> {
+        let piece_type = match c.to_ascii_lowercase() {
+            'k' => PieceType::King,
+            'q' => PieceType::Queen,
+            'r' => PieceType::Rook,
+            'n' => PieceType::Knight,
+            'b' => PieceType::Bishop,
+            'p' => PieceType::Pawn,
+            _ => return None,
+        };
+        let colour = if c.is_ascii_uppercase() {
+            Colour::White
+        } else {
+            Colour::Black
+        };
+        Some(Piece { piece_type, colour })
+    }
+
+    /// A function that returns the character representing the piece in FEN, uppercase for white and lowercase for black.
+    fn to_fen_char(self) -> char {
+        let c = match self.piece_type {
+            PieceType::King => 'k',
+            PieceType::Queen => 'q',
+            PieceType::Rook => 'r',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Pawn => 'p',
+        };
+        match self.colour {
+            Colour::White => c.to_ascii_uppercase(),
+            Colour::Black => c,
+        }
+    }
+
+    /// A function that returns the Unicode chess glyph of the piece, e.g. `♔` for a white king and `♚` for a black king.
+    fn to_unicode_char(self) -> char {
+        match (self.colour, self.piece_type) {
+            (Colour::White, PieceType::King) => '♔',
+            (Colour::White, PieceType::Queen) => '♕',
+            (Colour::White, PieceType::Rook) => '♖',
+            (Colour::White, PieceType::Bishop) => '♗',
+            (Colour::White, PieceType::Knight) => '♘',
+            (Colour::White, PieceType::Pawn) => '♙',
+            (Colour::Black, PieceType::King) => '♚',
+            (Colour::Black, PieceType::Queen) => '♛',
+            (Colour::Black, PieceType::Rook) => '♜',
+            (Colour::Black, PieceType::Bishop) => '♝',
+            (Colour::Black, PieceType::Knight) => '♞',
+            (Colour::Black, PieceType::Pawn) => '♟',
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Struct for some position. Contains the fields `row` and `col` corresponding to the row and col represented, individually,
 /// as well as the field `idx` corresponding to the index of the position in the board array.
 ///
@@ -79,17 +284,28 @@ pub struct Position {
 }
 
 impl Position {
+    /// Returns the row of the position as an index 0-7, where 0 is the first row.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Returns the column of the position as an index 0-7, where 0 is the a-file.
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    /// Returns the index of the position in the board array.
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
     /// Init-function that parses some position on the chessboard from the corresponding row and col as indices 0-7.
     ///
     /// Returns an `Ok(Position)`,
-    /// or an `Err(&str)` describing the error if the input does not represent some part of the chess board.
-    pub fn new(row: usize, col: usize) -> Result<Position, String> {
-        if row > 8 || col > 8 {
-            let error = format!(
-                "Invalid row: {} or col: {} input. Input should be between 0-7.",
-                row, col
-            );
-            return Err(error);
+    /// or an `Err(ChessError)` describing the error if the input does not represent some part of the chess board.
+    pub fn new(row: usize, col: usize) -> Result<Position, ChessError> {
+        if row > 7 || col > 7 {
+            return Err(ChessError::OffBoard);
         }
 
         return Ok(Position {
@@ -102,11 +318,10 @@ impl Position {
     /// Init-function that parses some position on the chessboard from the corresponding array index 0-63.
     ///
     /// Returns an `Ok(Position)`,
-    /// or an `Err(&str)` describing the error if the input does not represent some part of the chess board.
-    pub fn new_from_idx(idx: usize) -> Result<Position, String> {
+    /// or an `Err(ChessError)` describing the error if the input does not represent some part of the chess board.
+    pub fn new_from_idx(idx: usize) -> Result<Position, ChessError> {
         if idx > 63 {
-            let error = format!("Invalid idx: {} input. Input should be between 0-63.", idx);
-            return Err(error);
+            return Err(ChessError::OffBoard);
         }
 
         return Ok(Position {
@@ -116,19 +331,20 @@ impl Position {
         });
     }
 
-    /// Init-function that parses some position on the chessboard from a two character String on the format `XF` where `X` is a character a-h and `F` is a number 0-7. Performs trimming and caps-handling.
+    /// Init-function that parses some position on the chessboard from a two character String on the format `XF` where `X` is a character a-h and `F` is a number 0-7.
+    /// Performs caps-handling and ignores any whitespace, such that e.g. `"  e2 "` and `"E2"` are both parsed as e2.
     ///
     /// Returns an `Ok(Position)`,
-    /// or an `Err(&str)` describing the error if the input does not represent some part of the chess board.
-    pub fn parse_str(str: &str) -> Result<Position, String> {
+    /// or an `Err(ChessError)` describing the error if the input does not represent some part of the chess board.
+    pub fn parse_str(str: &str) -> Result<Position, ChessError> {
         let str_lowercase = str.to_lowercase(); // Performed to permit uppercase inputs. Saved in a memory to permit safe borrowing.
         let chars: Vec<char> = str_lowercase
-            .trim() // Removes potential whitespaces passed to the function
             .chars()
+            .filter(|c| !c.is_whitespace()) // Removes potential whitespaces passed to the function, also between the characters
             .collect(); // Creates the vector
 
         if chars.len() != 2 {
-            return Err(String::from(format!("Input {} is of invalid length.", str)));
+            return Err(ChessError::ParseError(format!("Input '{}' is of invalid length.", str.trim())));
         }
 
         // parses the first character: the column; throws an error if the character is not a character between a-h
@@ -146,7 +362,7 @@ impl Position {
                     "First character '{}' of string invalid, should be some character between a-h",
                     chars[0]
                 );
-                return Err(error);
+                return Err(ChessError::ParseError(error));
             }
         };
 
@@ -166,20 +382,40 @@ impl Position {
                     "Second character '{}' of string invalid, should be some number between 1-8",
                     chars[1]
                 );
-                return Err(error);
+                return Err(ChessError::ParseError(error));
             }
         };
 
         return Position::new(row, col);
     }
 
+    /// Function that validates some user input as a square on the chessboard and returns it on the canonical format `XF`,
+    /// e.g. `" E4 "` becomes `"e4"`. Performs trimming and caps-handling.
+    ///
+    /// Returns an `Ok(String)`,
+    /// or an `Err(ChessError)` describing the error if the input does not represent some part of the chess board.
+    pub fn normalize_square(input: &str) -> Result<String, ChessError> {
+        let pos = Position::parse_str(input)?;
+        Ok(pos.to_string())
+    }
+
+    /// Function that returns the position at offset from self, given as a tuple (row-offset, col-offset), without modifying self.
+    ///
+    /// Returns an `Ok(Position)`,
+    /// or an `Err(ChessError)` if the new position is not on the board.
+    pub fn offset(&self, delta: (i32, i32)) -> Result<Position, ChessError> {
+        let mut pos = *self;
+        pos.offset_self(delta)?;
+        Ok(pos)
+    }
+
     /// Function that modifies self by offset, given as a tuple (row-offset, col-offset)
-    pub fn offset_self(&mut self, offset: (i32, i32)) -> Result<bool, String> {
+    pub fn offset_self(&mut self, offset: (i32, i32)) -> Result<bool, ChessError> {
         let row_result: i32 = self.row as i32 + offset.0;
         let col_result: i32 = self.col as i32 + offset.1;
 
         if row_result < 0 || row_result > 7 || col_result < 0 || col_result > 7 {
-            return Err(String::from("New position not on board."));
+            return Err(ChessError::OffBoard);
         }
 
         // We are fine and complete the addition
@@ -190,31 +426,510 @@ impl Position {
     }
 }
 
+/// Enum for the side of the board that a king castles towards. Is implemented by `MoveResult`.
+///
+/// Contains the variants `Kingside` and `Queenside`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CastleSide {
+    Kingside,
+    Queenside,
+}
+
+/// Struct describing the outcome of a move, returned by `Game::make_move_detailed`.
+///
+/// Contains the fields
+/// - `state`, the state of the game after the move.
+/// - `captured`, the piece that was captured by the move, if any.
+/// - `was_check`, whether the move put the opponent's king in check (including checkmate).
+/// - `was_castle`, the side castled towards if the move was castling. Castling is not implemented yet, so this is always `None`.
+/// - `was_en_passant`, whether the move was an en passant capture. En passant is not implemented yet, so this is always `false`.
+/// - `san`, the move in Standard Algebraic Notation, e.g. `Nf3` or `exd5+`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MoveResult {
+    pub state: GameState,
+    pub captured: Option<Piece>,
+    pub was_check: bool,
+    pub was_castle: Option<CastleSide>,
+    pub was_en_passant: bool,
+    pub san: String,
+}
+
+/// Struct for the clock of a timed game, keeping the time remaining for each colour. Is used by `Game::set_clock`.
+///
+/// Each colour starts with the same time, and `increment` is added to the time of a colour every time it completes a turn in time.
+/// Only the turn of one colour runs at a time, from `start_turn` until the colour presses the clock with `press`.
+#[derive(Clone, Debug)]
+pub struct GameClock {
+    white_remaining: Duration,
+    black_remaining: Duration,
+    increment: Duration,
+    running: Option<(Colour, Instant)>,
+}
+
+impl GameClock {
+    /// Init-function for a clock giving each colour `time` and adding `increment` after every turn. No turn is running yet.
+    pub fn new(time: Duration, increment: Duration) -> GameClock {
+        GameClock {
+            white_remaining: time,
+            black_remaining: time,
+            increment,
+            running: None,
+        }
+    }
+
+    /// Starts the turn of `colour`, such that its time runs from now on. Stops the turn of the other colour without charging it any time.
+    pub fn start_turn(&mut self, colour: Colour) {
+        self.running = Some((colour, Instant::now()));
+    }
+
+    /// Ends the turn of `colour`, charging it the time since its turn started, and starts the turn of the other colour.
+    /// If the turn of `colour` was not running, it is not charged any time.
+    ///
+    /// Returns whether `colour` completed its turn in time. If it did not, its time is 0, the clock is stopped and no increment is added.
+    pub fn press(&mut self, colour: Colour) -> bool {
+        let remaining = self.time_remaining(colour);
+        if remaining == Duration::ZERO {
+            self.set_remaining(colour, remaining);
+            self.running = None;
+            return false;
+        }
+
+        // Only charge the colour, and give it the increment, if it was its turn
+        if let Some((running_colour, _)) = self.running {
+            if running_colour == colour {
+                self.set_remaining(colour, remaining + self.increment);
+            }
+        }
+        self.start_turn(colour.opposite());
+        true
+    }
+
+    /// Returns the time remaining for `colour`, including the time spent on its turn so far if its turn is running.
+    pub fn time_remaining(&self, colour: Colour) -> Duration {
+        let remaining = match colour {
+            Colour::White => self.white_remaining,
+            Colour::Black => self.black_remaining,
+        };
+        match self.running {
+            Some((running_colour, started)) if running_colour == colour => remaining.saturating_sub(started.elapsed()),
+            _ => remaining,
+        }
+    }
+
+    /// Sets the time remaining for `colour`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn set_remaining(&mut self, colour: Colour, remaining: Duration) {
+        match colour {
+            Colour::White => self.white_remaining = remaining,
+            Colour::Black => self.black_remaining = remaining,
+        }
+    }
+}
+
+/// Serialization of the board as a sequence of its 64 squares, since serde only implements its traits for arrays of at most 32 elements.
+#[cfg(feature = "serde")]
+mod board_serde {
+    use super::Piece;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::convert::TryInto;
+
+    pub fn serialize<S: Serializer>(board: &[Option<Piece>; 8 * 8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(board.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[Option<Piece>; 8 * 8], D::Error> {
+        let squares: Vec<Option<Piece>> = Vec::deserialize(deserializer)?;
+        squares
+            .try_into()
+            .map_err(|squares: Vec<Option<Piece>>| D::Error::invalid_length(squares.len(), &"a board of 64 squares"))
+    }
+}
+
+/// Struct for a move from the position `from` to the position `to`. Is used by e.g. `Game::apply_move`.
+///
+/// Contains the fields `from` and `to` of type Position, as well as the field `promotion` of type Option<PieceType>
+/// which holds the piece a pawn is promoted to if the move promotes a pawn, else `None`.
+/// A move that takes a pawn to the last row always promotes it, so it must have a promotion to be applied, see `Game::is_promotion_move`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move {
+    pub from: Position,
+    pub to: Position,
+    pub promotion: Option<PieceType>,
+}
+
+impl Move {
+    /// Init-function for a move from `from` to `to` that does not promote a pawn.
+    pub fn new(from: Position, to: Position) -> Move {
+        Move {
+            from,
+            to,
+            promotion: None,
+        }
+    }
+
+    /// Init-function for a move from `from` to `to` that promotes a pawn to `promotion`.
+    pub fn new_promotion(from: Position, to: Position, promotion: PieceType) -> Move {
+        Move {
+            from,
+            to,
+            promotion: Some(promotion),
+        }
+    }
+
+    /// Init-function that parses a move from two positions on the format `XF`, see `Position::parse_str`. The move does not promote a pawn.
+    ///
+    /// Returns an `Ok(Move)`,
+    /// or an `Err(ChessError)` describing the error if either position could not be parsed.
+    pub fn parse_str(from_str: &str, to_str: &str) -> Result<Move, ChessError> {
+        Ok(Move::new(Position::parse_str(from_str)?, Position::parse_str(to_str)?))
+    }
+
+    /// Init-function that parses a move in the coordinate notation of the Universal Chess Interface (UCI), e.g. `e2e4` or `e7e8q`,
+    /// where a fifth character gives the piece a pawn is promoted to. Performs trimming and caps-handling.
+    ///
+    /// ```
+    /// use chess_template::{Move, PieceType};
+    ///
+    /// let mv = Move::parse_uci("a7a8q").unwrap();
+    /// assert_eq!(mv.promotion, Some(PieceType::Queen));
+    /// assert_eq!(mv.to_uci(), "a7a8q");
+    /// ```
+    ///
+    /// Returns an `Ok(Move)`,
+    /// or an `Err(ChessError)` describing the error if the input is not 4 or 5 characters long or either part could not be parsed.
+    pub fn parse_uci(uci: &str) -> Result<Move, ChessError> {
+        let uci = uci.trim().to_lowercase();
+        if !uci.is_ascii() || !(uci.len() == 4 || uci.len() == 5) {
+            return Err(ChessError::ParseError(format!("Invalid UCI move '{}', should be on the format 'e2e4' or 'e7e8q'.", uci)));
+        }
+
+        let from = Position::parse_str(&uci[0..2])?;
+        let to = Position::parse_str(&uci[2..4])?;
+        let promotion = match &uci[4..] {
+            "" => None,
+            "q" => Some(PieceType::Queen),
+            "r" => Some(PieceType::Rook),
+            "b" => Some(PieceType::Bishop),
+            "n" => Some(PieceType::Knight),
+            piece => return Err(ChessError::ParseError(format!("Invalid promotion piece '{}' in UCI move.", piece))),
+        };
+
+        Ok(Move { from, to, promotion })
+    }
+
+    /// Returns the move in the coordinate notation of the Universal Chess Interface (UCI), e.g. `e2e4` or `e7e8q`. See `parse_uci`.
+    pub fn to_uci(&self) -> String {
+        let promotion = match self.promotion {
+            None => "",
+            Some(PieceType::Queen) => "q",
+            Some(PieceType::Rook) => "r",
+            Some(PieceType::Bishop) => "b",
+            Some(PieceType::Knight) => "n",
+            Some(PieceType::King) => "k",
+            Some(PieceType::Pawn) => "p",
+        };
+        format!("{}{}{}", self.from, self.to, promotion)
+    }
+}
+
+/// Struct for an entry in the move history of a game, used by `Game::undo_move` to restore the position before the move.
+///
+/// Contains the state of the game before the move was made, including the bitboards of the board, together with the move itself and,
+/// if the move promoted a pawn, the chosen piece type.
+/// Castling and en passant are not implemented yet, so there is no such data to save.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct HistoryEntry {
+    #[cfg_attr(feature = "serde", serde(with = "board_serde"))]
+    board: [Option<Piece>; 8 * 8],
+    bitboards: Bitboards,
+    active_colour: Colour,
+    state: GameState,
+    last_moved_to: Position,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    result: Option<GameResult>,
+    draw_offer: Option<Colour>,
+    mv: (Position, Position),
+    promotion: Option<PieceType>,
+}
+
+/// Struct for a bitboard representation of a board, used internally to find attacked squares without scanning the board square by square.
+///
+/// Every `u64` holds one bit per square, where bit `idx` corresponds to the square with index `idx` in the board array.
+/// `pieces` contains one bitboard per colour and piece type, see `Bitboards::index`, and `occupancy` contains the squares occupied by each colour.
+/// `Game` keeps its bitboards up to date alongside the board array, such that they do not have to be built from the board for every query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Bitboards {
+    pieces: [u64; 12],
+    occupancy: [u64; 2],
+}
+
+impl Bitboards {
+    /// These are the directions of the sliding pieces as (row, column)-offsets, first the four of the rook and then the four of the bishop.
+    const DIRECTIONS: [(i32, i32); 8] = [(1, 0), (0, 1), (-1, 0), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+    /// These are the classical ray masks: the squares from each square to the edge of the board in each of `DIRECTIONS`, not including the square itself.
+    const RAYS: [[u64; 8 * 8]; 8] = Bitboards::generate_rays();
+
+    /// These are the squares attacked by a knight on each square.
+    const KNIGHT_ATTACKS: [u64; 8 * 8] =
+        Bitboards::generate_attacks([(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)]);
+
+    /// These are the squares attacked by a king on each square.
+    const KING_ATTACKS: [u64; 8 * 8] =
+        Bitboards::generate_attacks([(1, 1), (1, 0), (1, -1), (0, 1), (0, -1), (-1, 1), (-1, 0), (-1, -1)]);
+
+    /// These are the squares that a pawn of each colour attacks each square from, i.e. one row behind the square as seen from the pawn.
+    /// White pawns move towards higher rows, and black pawns towards lower rows.
+    const PAWN_ATTACKERS: [[u64; 8 * 8]; 2] = [
+        Bitboards::generate_attacks([(-1, 1), (-1, -1)]),
+        Bitboards::generate_attacks([(1, 1), (1, -1)]),
+    ];
+
+    /// These are the squares that pawns can attack from. Pawns on the first or last row are misplaced and cannot move, so they do not attack anything.
+    const PAWN_ROWS: u64 = 0x00FF_FFFF_FFFF_FF00;
+
+    /// Builds the bitboards of `board`.
+    fn from_board(board: &[Option<Piece>; 8 * 8]) -> Bitboards {
+        let mut bitboards = Bitboards {
+            pieces: [0; 12],
+            occupancy: [0; 2],
+        };
+
+        for (i, piece) in board.iter().enumerate() {
+            if let Some(piece) = piece {
+                bitboards.pieces[Bitboards::index(piece.piece_type, piece.colour)] |= 1 << i;
+                bitboards.occupancy[piece.colour as usize] |= 1 << i;
+            }
+        }
+
+        bitboards
+    }
+
+    /// Returns the index in `pieces` of the bitboard of the pieces of type `piece_type` and colour `colour`.
+    /// The order is the same as for the keys in `Game::ZOBRIST_KEYS`.
+    fn index(piece_type: PieceType, colour: Colour) -> usize {
+        colour as usize * 6 + piece_type as usize
+    }
+
+    /// Places `piece` on the square at index `idx`, or empties the square if `piece` is `None`, removing whatever piece stood there.
+    fn set_square(&mut self, idx: usize, piece: Option<Piece>) {
+        let bit: u64 = 1 << idx;
+        for bitboard in self.pieces.iter_mut().chain(self.occupancy.iter_mut()) {
+            *bitboard &= !bit;
+        }
+
+        if let Some(piece) = piece {
+            self.pieces[Bitboards::index(piece.piece_type, piece.colour)] |= bit;
+            self.occupancy[piece.colour as usize] |= bit;
+        }
+    }
+
+    /// Moves the piece at index `from` to index `to`, removing whatever piece stood at `to`.
+    fn move_piece(&mut self, from: usize, to: usize) {
+        let from_bit: u64 = 1 << from;
+        let to_bit: u64 = 1 << to;
+
+        for bitboard in self.pieces.iter_mut().chain(self.occupancy.iter_mut()) {
+            let had_piece_at_from = *bitboard & from_bit != 0;
+            *bitboard &= !(from_bit | to_bit);
+            if had_piece_at_from {
+                *bitboard |= to_bit;
+            }
+        }
+    }
+
+    /// Checks whether the square at index `idx` is attacked by some piece of colour `by` and returns a boolean, see `Game::is_square_attacked`.
+    ///
+    /// Sliding pieces are found by taking the first occupied square along each ray from the square, which is the lowest bit
+    /// for the directions towards higher indices and the highest bit for the others.
+    fn is_square_attacked(&self, idx: usize, by: Colour) -> bool {
+        let pieces_of = |piece_type: PieceType| self.pieces[Bitboards::index(piece_type, by)];
+        let occupied = self.occupancy[0] | self.occupancy[1];
+        let straight_sliders = pieces_of(PieceType::Rook) | pieces_of(PieceType::Queen);
+        let diagonal_sliders = pieces_of(PieceType::Bishop) | pieces_of(PieceType::Queen);
+
+        for (direction, (row_offset, col_offset)) in Bitboards::DIRECTIONS.iter().enumerate() {
+            let blockers = Bitboards::RAYS[direction][idx] & occupied;
+            if blockers == 0 {
+                continue;
+            }
+
+            let first_blocker: u64 = if row_offset * 8 + col_offset > 0 {
+                blockers & blockers.wrapping_neg()
+            } else {
+                1 << (63 - blockers.leading_zeros())
+            };
+            let sliders = if direction < 4 { straight_sliders } else { diagonal_sliders };
+            if first_blocker & sliders != 0 {
+                return true;
+            }
+        }
+
+        Bitboards::KNIGHT_ATTACKS[idx] & pieces_of(PieceType::Knight) != 0
+            || Bitboards::KING_ATTACKS[idx] & pieces_of(PieceType::King) != 0
+            || Bitboards::PAWN_ATTACKERS[by as usize][idx] & pieces_of(PieceType::Pawn) & Bitboards::PAWN_ROWS != 0
+    }
+
+    /// Checks whether `piece`, standing on the square at index `from`, attacks the square at index `to` and returns a boolean,
+    /// regardless of what stands on `to`, see `Game::attacks`.
+    ///
+    /// A sliding piece attacks `to` if `to` is on one of its rays and no square between `from` and `to` is occupied.
+    fn attacks(&self, piece: Piece, from: usize, to: usize) -> bool {
+        let from_bit: u64 = 1 << from;
+        let to_bit: u64 = 1 << to;
+
+        let directions = match piece.piece_type {
+            PieceType::Knight => return Bitboards::KNIGHT_ATTACKS[from] & to_bit != 0,
+            PieceType::King => return Bitboards::KING_ATTACKS[from] & to_bit != 0,
+            PieceType::Pawn => return Bitboards::PAWN_ATTACKERS[piece.colour as usize][to] & from_bit & Bitboards::PAWN_ROWS != 0,
+            PieceType::Rook => 0..4,
+            PieceType::Bishop => 4..8,
+            PieceType::Queen => 0..8,
+        };
+
+        let occupied = self.occupancy[0] | self.occupancy[1];
+        for direction in directions {
+            if Bitboards::RAYS[direction][from] & to_bit != 0 {
+                // the squares between are those on the ray from `from` that are neither `to` nor behind it
+                let between = Bitboards::RAYS[direction][from] & !Bitboards::RAYS[direction][to] & !to_bit;
+                return between & occupied == 0;
+            }
+        }
+
+        false
+    }
+
+    /// Generates the ray masks in `RAYS`.
+    const fn generate_rays() -> [[u64; 8 * 8]; 8] {
+        let mut rays = [[0; 8 * 8]; 8];
+
+        let mut direction = 0;
+        while direction < 8 {
+            let (row_offset, col_offset) = Bitboards::DIRECTIONS[direction];
+            let mut idx = 0;
+            while idx < 8 * 8 {
+                let mut row = idx as i32 / 8 + row_offset;
+                let mut col = idx as i32 % 8 + col_offset;
+                while row >= 0 && row < 8 && col >= 0 && col < 8 {
+                    rays[direction][idx] |= 1 << (row * 8 + col);
+                    row += row_offset;
+                    col += col_offset;
+                }
+                idx += 1;
+            }
+            direction += 1;
+        }
+
+        rays
+    }
+
+    /// Generates the squares reached from each square by each of `offsets`, given as (row, column)-offsets. Offsets outside of the board are left out.
+    const fn generate_attacks<const N: usize>(offsets: [(i32, i32); N]) -> [u64; 8 * 8] {
+        let mut attacks = [0; 8 * 8];
+
+        let mut idx = 0;
+        while idx < 8 * 8 {
+            let mut i = 0;
+            while i < N {
+                let row = idx as i32 / 8 + offsets[i].0;
+                let col = idx as i32 % 8 + offsets[i].1;
+                if row >= 0 && row < 8 && col >= 0 && col < 8 {
+                    attacks[idx] |= 1 << (row * 8 + col);
+                }
+                i += 1;
+            }
+            idx += 1;
+        }
+
+        attacks
+    }
+}
+
 /// The game! The struct contains our accessible fields and functions.
 ///
 /// * `new()` which instantiates the game.
+/// * `empty()` which instantiates a game with an empty board, to be set up by `place_piece(position, piece)` and `set_active_colour(colour)`.
 /// * `make_move(from_str, to_str)` which, if legal, makes a move from some pos XF to some pos XF and returns the resulting error or new GameState.
 /// * `get_game_state()` returns the state of the game.
 /// * `get_active_colour()` returns the active colour.
 /// * `get_board()` returns the board.
-/// * `get_possible_moves(position, recursion_order)` returns a list of all possible moves for the piece at position.
+/// * `possible_moves(position)` returns a list of all possible moves for the piece at position.
+/// * `get_possible_moves(position, recursion_order)` does the same as `possible_moves` and is kept for compatibility. `recursion_order` is ignored.
 /// * `set_promotion(piece)` should be called if the game is in GameState::WaitingOnPromotionChoice to indicate what piece to promote the last moved pawn to.
-///
-/// Also contains the constant `MAX_RECURSIONS` which defines how many orders of check-recursion should be checked by `get_possible_moves`.
-#[derive(Clone)] // The clone derivation is necessary as it is used by try_move
+/// * `set_promotion_piece(piece_type)` does the same as `set_promotion`, given a PieceType instead of a string.
+/// * `undo_move()` takes back the last move.
+#[derive(Clone)] // The clone derivation is necessary as it is used by the search functions
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     /* save board, active colour, ... */
     state: GameState,
     active_colour: Colour,
+    #[cfg_attr(feature = "serde", serde(with = "board_serde"))]
     board: [Option<Piece>; 8 * 8],
+    bitboards: Bitboards,
     last_moved_to: Position,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    result: Option<GameResult>,
+    draw_offer: Option<Colour>,
+    auto_promote: Option<PieceType>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    clock: Option<GameClock>,
+    history: Vec<HistoryEntry>,
+    position_counts: HashMap<u64, u8>,
 }
 
 /// Here we implement the main functions of our game.
 impl Game {
-    /// This is a constant used in the function `try_move` that specifies how far the engine should check for Check-states.
-    /// The value 2 should do since after 2 recursions, we have checked each user making the next move. In this time, we should discover all relevant Check-states.
-    const MAX_RECURSIONS: i32 = 2;
+    /// This is the number of halfmoves without a capture or a pawn move after which a draw by the fifty-move rule is claimable.
+    const FIFTY_MOVE_RULE_HALFMOVES: u32 = 100;
+
+    /// This is the number of halfmoves without a capture or a pawn move after which the game is drawn by the seventy-five-move rule without a claim.
+    const SEVENTY_FIVE_MOVE_RULE_HALFMOVES: u32 = 150;
+
+    /// This is the number of times a position has to occur for a draw by threefold repetition to be claimable.
+    const THREEFOLD_REPETITION_OCCURRENCES: u8 = 3;
+
+    /// This is the number of times a position has to occur for the game to be drawn by fivefold repetition without a claim.
+    const FIVEFOLD_REPETITION_OCCURRENCES: u8 = 5;
+
+    /// This is the score given to a checkmate by the search. Mates found closer to the root are given scores of higher magnitude,
+    /// such that the search prefers shorter mates.
+    const MATE_SCORE: i32 = 100_000;
+
+    /// This is the deepest the iterative deepening in `best_move_with_limit` will search, regardless of the node limit.
+    const MAX_SEARCH_DEPTH: u32 = 64;
+
+    /// These are the random keys used by the function `zobrist_hash`, one per piece type, colour and square followed by one for black to move.
+    /// They are generated from a fixed seed at compile time such that hashes are stable across runs.
+    const ZOBRIST_KEYS: [u64; 12 * 64 + 1] = Game::generate_zobrist_keys();
+
+    /// This is a table of well-known openings used by the function `eco_code`.
+    /// Each entry contains the ECO code of the opening and the moves leading to it on the format "XF XF".
+    const OPENINGS: [(&'static str, &'static str); 16] = [
+        ("A04", "g1 f3"),
+        ("A10", "c2 c4"),
+        ("A40", "d2 d4"),
+        ("B00", "e2 e4"),
+        ("B01", "e2 e4 d7 d5"),
+        ("B02", "e2 e4 g8 f6"),
+        ("B10", "e2 e4 c7 c6"),
+        ("B20", "e2 e4 c7 c5"),
+        ("C00", "e2 e4 e7 e6"),
+        ("C20", "e2 e4 e7 e5"),
+        ("C30", "e2 e4 e7 e5 f2 f4"),
+        ("C44", "e2 e4 e7 e5 g1 f3 b8 c6"),
+        ("C50", "e2 e4 e7 e5 g1 f3 b8 c6 f1 c4"),
+        ("C60", "e2 e4 e7 e5 g1 f3 b8 c6 f1 b5"),
+        ("D06", "d2 d4 d7 d5 c2 c4"),
+        ("E60", "d2 d4 g8 f6 c2 c4 g7 g6"),
+    ];
 
     /// Initialises a new board with pieces.
     pub fn new() -> Game {
@@ -271,28 +986,66 @@ impl Game {
 
         // initializing board array
         let board_init = [
-            w_rook, w_knight, w_bishop, w_king, w_queen, w_bishop, w_knight, w_rook, w_pawn,
+            w_rook, w_knight, w_bishop, w_queen, w_king, w_bishop, w_knight, w_rook, w_pawn,
             w_pawn, w_pawn, w_pawn, w_pawn, w_pawn, w_pawn, w_pawn, None, None, None, None, None,
             None, None, None, None, None, None, None, None, None, None, None, None, None, None,
             None, None, None, None, None, None, None, None, None, None, None, None, None, b_pawn,
             b_pawn, b_pawn, b_pawn, b_pawn, b_pawn, b_pawn, b_pawn, b_rook, b_knight, b_bishop,
-            b_king, b_queen, b_bishop, b_knight, b_rook,
+            b_queen, b_king, b_bishop, b_knight, b_rook,
         ];
 
-        Game {
+        let mut game = Game {
             /* initialise board, set active colour to white and state to in progress */
             state: GameState::InProgress,
             active_colour: Colour::White,
             board: board_init,
+            bitboards: Bitboards::from_board(&board_init),
             last_moved_to: Position::new(0, 0).unwrap(), // arbitrary position, is updated before it is used
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            result: None,
+            draw_offer: None,
+            auto_promote: None,
+            clock: None,
+            history: Vec::new(),
+            position_counts: HashMap::new(),
+        };
+        // the starting position counts as the first occurrence of it
+        game.record_position();
+        game
+    }
+
+    /// Initialises a game with an empty board and White to move, for setting up positions with `place_piece` and `set_active_colour`.
+    ///
+    /// Note that the position is invalid, since it has no kings, until a king of each colour has been placed.
+    /// The state of the game is only computed from then on, and the pieces of a colour without a king have no legal moves.
+    pub fn empty() -> Game {
+        let mut game = Game::new();
+        game.set_board([None; 8 * 8]);
+        game.restart_from_position();
+        game
+    }
+
+    /// Initialises a new board and makes the moves in `moves` on it, each given as a pair of positions on the format XF.
+    ///
+    /// Returns an `Ok(Game)`,
+    /// or the `Err(ChessError)` of the first move that could not be made.
+    pub fn new_from_moves(moves: &[(&str, &str)]) -> Result<Game, ChessError> {
+        let mut game = Game::new();
+        for (from_str, to_str) in moves {
+            game.make_move(from_str, to_str)?;
         }
+        Ok(game)
     }
 
     /// If the current game state is InProgress or Check and the move is legal,
     /// move a piece and return the resulting state of the game. Performs trimmming and caps-handling.
     ///
+    /// If a piece type has been set by `set_auto_promote`, a pawn reaching the last row is promoted to it directly,
+    /// instead of the game waiting on a promotion choice.
+    ///
     /// Updates all fields.
-    pub fn make_move(&mut self, from_str: &str, to_str: &str) -> Result<GameState, String> {
+    pub fn make_move(&mut self, from_str: &str, to_str: &str) -> Result<GameState, ChessError> {
         // parse from_str
         let from_pos = match Position::parse_str(&from_str) {
             Ok(result) => result,
@@ -305,37 +1058,42 @@ impl Game {
             Err(string) => return Err(string),
         };
 
-        return self.make_move_pos(from_pos, to_pos);
+        let state = self.make_move_pos(from_pos, to_pos)?;
+        if state == GameState::WaitingOnPromotionChoice {
+            if let Some(piece_type) = self.auto_promote {
+                return self.set_promotion_piece(piece_type);
+            }
+        }
+        Ok(state)
     }
 
     /// (Variant of `make_move` that takes Positions as input instead.)
+    /// Unlike `make_move`, this never promotes automatically, see `set_auto_promote`.
+    ///
+    /// In a timed game, see `set_clock`, the clock is pressed for the active colour. If it has run out of time, the move is not made,
+    /// and the game ends with GameState::GameOver and the opposite colour as the winner instead.
     /// If the current game state is InProgress or Check and the move is legal,
     /// move a piece and return the resulting state of the game.
     ///
     /// Updates all fields.
-    pub fn make_move_pos(&mut self, from_pos: Position, to_pos: Position ) -> Result<GameState, String> {
+    pub fn make_move_pos(&mut self, from_pos: Position, to_pos: Position ) -> Result<GameState, ChessError> {
         // Checks that the game state is InProgress or Check, else throws an error.
         if !(self.state == GameState::InProgress || self.state == GameState::Check) {
-            let error = format!("The game is not in a state where a move can be made. Currently, the state is {:?}.", self.state);
-            return Err(error);
+            return Err(ChessError::NotPlayable(self.state));
         }
 
         // check that the the piece is not None and is of the right colour
         match self.board[from_pos.idx] {
-            None => {
-                return Err(String::from(
-                    "There is no piece on the square you are trying to move from.",
-                ))
-            }
+            None => return Err(ChessError::NoPieceAtSource),
             Some(piece) => {
                 if piece.colour != self.active_colour {
-                    return Err(String::from("It is not this colour's turn!"));
+                    return Err(ChessError::WrongColour);
                 }
             }
         }
 
         // Generates a list of all the legal moves that the piece in question can be performed.
-        let possible_moves = self.get_possible_moves(from_pos, 0);
+        let possible_moves = self.possible_moves(from_pos);
 
         if !possible_moves
             .iter() // Creates an iterable of positions.
@@ -343,15 +1101,60 @@ impl Game {
         // Checks if our position is equal to some position in the list of possible moves. We use .any() since the objects may be different instances.
         {
             // eprintln!("Possible moves are {:?}", possible_moves); // DEBUG
-            return Err(String::from("Illegal move. (This might mean that this piece cannot move this way, or that it puts your king in check!)"));
+            Err(ChessError::IllegalMove)
         } else {
+            // in a timed game, the move only counts if it was made in time
+            if let Some(clock) = self.clock.as_mut() {
+                if !clock.press(self.active_colour) {
+                    self.state = GameState::GameOver;
+                    self.result = Some(GameResult::Timeout(self.active_colour.opposite()));
+                    return Ok(self.state);
+                }
+            }
+
+            // save the position before the move so that it can be taken back
+            self.history.push(HistoryEntry {
+                board: self.board,
+                bitboards: self.bitboards,
+                active_colour: self.active_colour,
+                state: self.state,
+                last_moved_to: self.last_moved_to,
+                halfmove_clock: self.halfmove_clock,
+                fullmove_number: self.fullmove_number,
+                result: self.result,
+                draw_offer: self.draw_offer,
+                mv: (from_pos, to_pos),
+                promotion: None,
+            });
+
+            // making a move instead of accepting a draw offer declines it
+            if self.draw_offer == Some(self.active_colour.opposite()) {
+                self.draw_offer = None;
+            }
+
+            // the halfmove clock counts the moves since the last capture or pawn move
+            // Unwrapping the piece at from_pos is safe here since it was checked above.
+            if self.board[to_pos.idx].is_some() || self.board[from_pos.idx].unwrap().piece_type == PieceType::Pawn {
+                self.halfmove_clock = 0;
+            } else {
+                self.halfmove_clock += 1;
+            }
+
+            // the fullmove number counts the moves by both players, so it increases after every move by Black
+            if self.active_colour == Colour::Black {
+                self.fullmove_number += 1;
+            }
+
             // We move the piece!
             self.board[to_pos.idx] = self.board[from_pos.idx];
             self.board[from_pos.idx] = None;
+            self.bitboards.move_piece(from_pos.idx, to_pos.idx);
             // and save this movement for future reference
             self.last_moved_to = to_pos;
             // and update the active colour (NEEDS TO BE DONE BEFORE update_game_state()!)
-            self.active_colour = Colour::opposite(self.active_colour);
+            self.active_colour = self.active_colour.opposite();
+            // and count the new position for the repetition rule
+            self.record_position();
             // and update the game state (to some variant of GameState)
             self.update_game_state();
 
@@ -359,98 +1162,409 @@ impl Game {
         }
     }
 
-    /// Checks the current game state for the player of the active_colour and updates it. Expects the active colour to be updated to the next player's colour.
+    /// Returns a new game where the move from `from` to `to` has been made as by `make_move_pos`, leaving this game untouched.
+    /// Useful for trying out a move and inspecting the result, e.g. in a search.
     ///
-    /// Updates only the field `state`.
+    /// Returns an error if the move cannot be made, see `make_move_pos`.
+    pub fn with_move(&self, from: Position, to: Position) -> Result<Game, ChessError> {
+        let mut game_after_movement = self.clone();
+        game_after_movement.make_move_pos(from, to)?;
+        Ok(game_after_movement)
+    }
+
+    /// Variant of `make_move` that returns a `MoveResult` describing the move instead of only the resulting state of the game.
     ///
-    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
-    fn update_game_state(&mut self) {
-        /*
-        If there is a pawn that needs to be promoted (is at the end of the board),
-        the method will put the game into GameState::WaitingOnPromotionChoice and skip the rest of the state-checking.
-        This is safe because the promotion method set_promotion will call this method again at the end to set the state to one of the below values.
-        */
-        if self.state != GameState::GameOver {
-            // Check if the user needs to promote a pawn by checking the piece at `last_moved_to`
-            let last_moved_piece = self.board[self.last_moved_to.idx].unwrap(); // unwrap is safe due since last_moved_to is well-defined.
-            if last_moved_piece.piece_type == PieceType::Pawn {
-                // We only care for pawns of the active colour.
-                // Unwrapping piece is safe here since it is not none.
-                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
-                if last_moved_piece.colour == Colour::White && self.last_moved_to.row == 7 {
-                    self.state = GameState::WaitingOnPromotionChoice;
-                    return;
-                } else if last_moved_piece.colour == Colour::Black && self.last_moved_to.row == 0 {
-                    self.state = GameState::WaitingOnPromotionChoice;
-                    return;
-                }
-            }
+    /// If the move promotes a pawn, the game will be waiting on a promotion choice and the SAN will lack the promotion suffix
+    /// and check marker, since these depend on the piece that is chosen.
+    ///
+    /// If the time of the active colour has run out in a timed game, the move is not made and there is nothing to describe.
+    /// The game then ends by the timeout as with `make_move_pos`, and `Err(ChessError::NotPlayable(GameState::GameOver))` is returned.
+    pub fn make_move_detailed(&mut self, from_str: &str, to_str: &str) -> Result<MoveResult, ChessError> {
+        let from_pos = Position::parse_str(from_str)?;
+        let to_pos = Position::parse_str(to_str)?;
+
+        // We perform the move on a clone first such that the SAN can be generated from the position before the move.
+        let game_after_movement = self.with_move(from_pos, to_pos)?;
+        let state = game_after_movement.state;
+
+        if let Some(GameResult::Timeout(_)) = game_after_movement.result {
+            *self = game_after_movement;
+            return Err(ChessError::NotPlayable(state));
         }
-        /* If the next thing to happen is not a promotion:
-        If the king is in check and no correcting move can be made, the game is in checkmate with GameState::GameOver.
-        If the king is in check and a correcting move can be made, the game is in check with GameState::Check.
-        If the king is not in check yet no move can be made, the game is in stalemate with GameState::GameOver.
-        If the king is not in check and some move can be made, the game is simply in progress with GameState::InProgress.
 
-        Note that the method `can_make_legal_move` primarily uses the function `get_possible_moves` which checks whether
-        some move puts the king in check when it is performed. A "possible" or "legal" move is thus defined as a move that
-        can be performed without putting the king at risk.
-        */
-        if self.is_in_check(self.active_colour, 0) {
-            if self.can_make_legal_move(self.active_colour) {
+        let result = MoveResult {
+            state,
+            captured: self.board[to_pos.idx],
+            was_check: game_after_movement.is_in_check(game_after_movement.active_colour),
+            was_castle: None,
+            was_en_passant: false,
+            san: self.move_to_san(from_pos, to_pos, None)?,
+        };
+
+        *self = game_after_movement;
+        Ok(result)
+    }
+
+    /// Returns the move from `from_pos` to `to_pos` in Standard Algebraic Notation (SAN), e.g. `Nf3`, `exd5`, `Nbd2` or `e8=Q+`,
+    /// given the current position as the position before the move. `promotion` is the piece that a pawn is promoted to, if any.
+    ///
+    /// The origin of the piece is added when another identical piece can move to the same square, and the suffixes `+` and `#`
+    /// are added for check and checkmate. If the move promotes a pawn but `promotion` is `None`, the promotion suffix and check marker are left out.
+    /// Castling is not implemented, so `O-O` and `O-O-O` are never returned.
+    ///
+    /// Returns an `Ok(String)`,
+    /// or an `Err(ChessError)` if the move is not legal for the active colour or if `promotion` is given for a move that does not promote a pawn.
+    pub fn move_to_san(&self, from_pos: Position, to_pos: Position, promotion: Option<PieceType>) -> Result<String, ChessError> {
+        if !self.is_move_legal(from_pos, to_pos) {
+            return Err(ChessError::IllegalMove);
+        }
+
+        // Unwrapping is safe since the move is legal.
+        let piece = self.board[from_pos.idx].unwrap();
+        let is_capture = self.board[to_pos.idx].is_some();
+        let from_str = from_pos.to_string();
+
+        let mut san = String::new();
+
+        if piece.piece_type == PieceType::Pawn {
+            // Pawn captures are identified by the file the pawn moves from
+            if is_capture {
+                san.push_str(&from_str[0..1]);
+            }
+        } else {
+            san.push_str(match piece.piece_type {
+                PieceType::King => "K",
+                PieceType::Queen => "Q",
+                PieceType::Rook => "R",
+                PieceType::Knight => "N",
+                PieceType::Bishop => "B",
+                PieceType::Pawn => "",
+            });
+
+            // If another identical piece can move to the same square, we add the file, rank or both of the origin to disambiguate
+            let mut shares_file = false;
+            let mut shares_rank = false;
+            let mut is_ambiguous = false;
+            for (i, other) in self.board.iter().enumerate() {
+                if i == from_pos.idx || *other != Some(piece) {
+                    continue;
+                }
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let other_pos = Position::new_from_idx(i).unwrap();
+                if self.possible_moves(other_pos).contains(&to_pos) {
+                    is_ambiguous = true;
+                    shares_file = shares_file || other_pos.col == from_pos.col;
+                    shares_rank = shares_rank || other_pos.row == from_pos.row;
+                }
+            }
+
+            if is_ambiguous {
+                if !shares_file {
+                    san.push_str(&from_str[0..1]);
+                } else if !shares_rank {
+                    san.push_str(&from_str[1..2]);
+                } else {
+                    san.push_str(&from_str);
+                }
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&to_pos.to_string());
+
+        // Perform the move to find the promotion suffix and whether the move checks or mates
+        let mut game_after_movement = self.clone();
+        // The move is only tried out, so it should not press the clock
+        game_after_movement.clock = None;
+        // unwrap is safe since the move is legal
+        game_after_movement.make_move_pos(from_pos, to_pos).unwrap();
+        if game_after_movement.state != GameState::WaitingOnPromotionChoice && promotion.is_some() {
+            return Err(ChessError::InvalidPromotion(format!("The move {} {} does not promote a pawn.", from_pos, to_pos)));
+        }
+        if game_after_movement.state == GameState::WaitingOnPromotionChoice {
+            match promotion {
+                None => return Ok(san),
+                Some(piece_type) => {
+                    san.push_str(match piece_type {
+                        PieceType::Queen => "=Q",
+                        PieceType::Rook => "=R",
+                        PieceType::Knight => "=N",
+                        PieceType::Bishop => "=B",
+                        PieceType::King | PieceType::Pawn => "",
+                    });
+                    game_after_movement.set_promotion_piece(piece_type)?;
+                }
+            }
+        }
+
+        let opponent = piece.colour.opposite();
+        if game_after_movement.is_in_check(opponent) {
+            if game_after_movement.can_make_legal_move(opponent) {
+                san.push('+');
+            } else {
+                san.push('#');
+            }
+        }
+
+        Ok(san)
+    }
+
+    /// Checks the current game state for the player of the active_colour and updates it. Expects the active colour to be updated to the next player's colour.
+    ///
+    /// Updates only the field `state`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn update_game_state(&mut self) {
+        /*
+        If there is a pawn that needs to be promoted (is at the end of the board),
+        the method will put the game into GameState::WaitingOnPromotionChoice and skip the rest of the state-checking.
+        This is safe because the promotion method set_promotion will call this method again at the end to set the state to one of the below values.
+        */
+        if !matches!(self.state, GameState::GameOver | GameState::Draw) {
+            // Check if the user needs to promote a pawn by checking the piece at `last_moved_to`
+            // The square may be empty if no move has been made yet, e.g. in a game loaded by `from_fen`.
+            if let Some(last_moved_piece) = self.board[self.last_moved_to.idx] {
+                if last_moved_piece.piece_type == PieceType::Pawn {
+                    // We only care for pawns of the active colour.
+                    if last_moved_piece.colour == Colour::White && self.last_moved_to.row == 7 {
+                        self.state = GameState::WaitingOnPromotionChoice;
+                        return;
+                    } else if last_moved_piece.colour == Colour::Black && self.last_moved_to.row == 0 {
+                        self.state = GameState::WaitingOnPromotionChoice;
+                        return;
+                    }
+                }
+            }
+        }
+        /* If the next thing to happen is not a promotion:
+        If the king is in check and no correcting move can be made, the game is in checkmate with GameState::GameOver.
+        If the king is in check and a correcting move can be made, the game is in check with GameState::Check.
+        If the king is not in check yet no move can be made, the game is in stalemate with GameState::Draw.
+        If the king is not in check and some move can be made, the game is simply in progress with GameState::InProgress.
+        If the game is not over, yet neither colour has the material to checkmate, the game is drawn by insufficient material with GameState::Draw.
+        If the game is not over, yet 75 moves by each player have been made without a capture or a pawn move, the game is drawn by the seventy-five-move rule with GameState::Draw.
+        (A draw by the fifty-move rule has to be claimed with `claim_fifty_move_draw`, so it is not checked here.)
+        If the game is not over, yet the current position has occurred five times, the game is drawn by fivefold repetition with GameState::Draw.
+        (A draw by threefold repetition has to be claimed with `claim_threefold_repetition`, so it is not checked here.)
+        The result of the game is saved alongside the state, such that `game_result` can tell the different endings apart.
+
+        Note that the method `can_make_legal_move` primarily uses the function `get_possible_moves` which checks whether
+        some move puts the king in check when it is performed. A "possible" or "legal" move is thus defined as a move that
+        can be performed without putting the king at risk.
+        */
+        self.result = None;
+        if self.is_in_check(self.active_colour) {
+            if self.can_make_legal_move(self.active_colour) {
                 self.state = GameState::Check;
             } else {
                 self.state = GameState::GameOver;
+                self.result = Some(GameResult::Checkmate(self.active_colour.opposite()));
             }
         } else {
             if self.can_make_legal_move(self.active_colour) {
-                // We have a stalemate
                 self.state = GameState::InProgress;
             } else {
-                self.state = GameState::GameOver;
+                // We have a stalemate
+                self.state = GameState::Draw;
+                self.result = Some(GameResult::Stalemate);
             }
         }
+
+        if self.result.is_none() && self.has_insufficient_material() {
+            self.state = GameState::Draw;
+            self.result = Some(GameResult::DrawByRule(DrawRule::InsufficientMaterial));
+        }
+
+        if self.result.is_none() && self.halfmove_clock >= Game::SEVENTY_FIVE_MOVE_RULE_HALFMOVES {
+            self.state = GameState::Draw;
+            self.result = Some(GameResult::DrawByRule(DrawRule::SeventyFiveMoveRule));
+        }
+
+        if self.result.is_none() && self.position_occurrences() >= Game::FIVEFOLD_REPETITION_OCCURRENCES {
+            self.state = GameState::Draw;
+            self.result = Some(GameResult::DrawByRule(DrawRule::FivefoldRepetition));
+        }
     }
 
-    /// Checks whether the king of colour `colour` is in check and returns a boolean. `recursion_order` should be set to 0 unless you know what you're doing.
-    /// This is done by iterating over every piece of the opposite colour and checking whether it can move to the king.
+    /// Returns the number of times the current position has occurred in the game, including now.
     ///
-    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS. If you are wondering whether the game is in state Check, please use `get_game_state` instead.
-    /// This function is public such that it can be called by `get_possible_moves` on cloned instances.
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn position_occurrences(&self) -> u8 {
+        self.position_counts.get(&self.zobrist_hash()).copied().unwrap_or(0)
+    }
+
+    /// Returns whether `other` represents the same position as this game, regardless of how the position was reached.
+    /// A position is given by the board and the active colour, since castling and en passant are not implemented.
+    /// The move history, the move counters and the state of the game are not compared.
+    pub fn same_position(&self, other: &Game) -> bool {
+        self.board == other.board && self.active_colour == other.active_colour
+    }
+
+    /// Returns the Zobrist hash of the position, e.g. for transposition tables and for detecting repeated positions.
+    /// The hash is the XOR of a random key for each piece on its square, and of another key if black is to move.
+    /// Castling and en passant are not implemented, so they are not part of the hash.
+    ///
+    /// Games with the same position always have the same hash, see `same_position`, and the hash is stable across runs.
+    pub fn zobrist_hash(&self) -> u64 {
+        let mut hash = 0;
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if let Some(piece) = piece {
+                hash ^= Game::ZOBRIST_KEYS[(piece.colour as usize * 6 + piece.piece_type as usize) * 64 + i];
+            }
+        }
+        if self.active_colour == Colour::Black {
+            hash ^= Game::ZOBRIST_KEYS[12 * 64];
+        }
+
+        hash
+    }
+
+    /// Generates the keys in `ZOBRIST_KEYS` with the SplitMix64 generator from a fixed seed.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    const fn generate_zobrist_keys() -> [u64; 12 * 64 + 1] {
+        let mut keys = [0; 12 * 64 + 1];
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+
+        let mut i = 0;
+        while i < keys.len() {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            keys[i] = z ^ (z >> 31);
+            i += 1;
+        }
+
+        keys
+    }
+
+    /// Counts one more occurrence of the current position.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn record_position(&mut self) {
+        *self.position_counts.entry(self.zobrist_hash()).or_insert(0) += 1;
+    }
+
+    /// Treats the current position as the starting position of the game, e.g. after it has been set up by `place_piece`.
+    /// The move history, the repetition counts, the result and any draw offer are cleared, the halfmove clock and the fullmove number
+    /// start over at 0 and 1, and the state of the game is computed from the position if there is exactly one king of each colour on the board.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn restart_from_position(&mut self) {
+        self.history.clear();
+        self.halfmove_clock = 0;
+        self.fullmove_number = 1;
+        self.position_counts.clear();
+        self.record_position();
+        self.result = None;
+        self.draw_offer = None;
+        self.state = GameState::InProgress;
+
+        let count_kings = |colour: Colour| {
+            self
+                .board
+                .iter()
+                .filter(|piece| **piece == Some(Piece { piece_type: PieceType::King, colour }))
+                .count()
+        };
+        if count_kings(Colour::White) == 1 && count_kings(Colour::Black) == 1 {
+            self.update_game_state();
+        }
+    }
+
+    /// Counts one less occurrence of the current position, e.g. when the move that led to it is taken back.
     ///
-    /// Note that this function calls `get_possible_moves` again which calls this function.
-    /// To avoid infinite recursion, we pass the variable `recursion_order` which is incremented by `get_possible_moves`.
-    fn is_in_check(&self, colour: Colour, recursion_order: i32) -> bool {
-        let king_pos = self.find_king_pos(colour);
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn forget_position(&mut self) {
+        let hash = self.zobrist_hash();
+        if let Some(count) = self.position_counts.get_mut(&hash) {
+            *count -= 1;
+            if *count == 0 {
+                self.position_counts.remove(&hash);
+            }
+        }
+    }
 
-        // eprintln!("Assessing game variant for king of colour {:?}:\n{}\n", colour, self); // DEBUG
+    /// Returns whether neither colour has the material to checkmate, i.e. whether only the kings remain together with
+    /// at most a single knight or bishop, or with any number of bishops that all stand on squares of the same colour.
+    /// This covers K vs K, KB vs K, KN vs K and KB vs KB with bishops on the same square colour.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn has_insufficient_material(&self) -> bool {
+        let mut knights = 0;
+        let mut bishops_on_light_square = 0;
+        let mut bishops_on_dark_square = 0;
 
         for (i, piece) in self.board.iter().enumerate() {
             if piece.is_none() {
                 // Do nothing
-            } else if piece.unwrap().colour != colour {
-                // eprintln!("Possible moves assessed for {:?} at pos {:?}.", piece, Position::new_from_idx(i)); // DEBUG
+            } else {
                 // Unwrapping piece is safe here since it is not none.
                 // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
-                let possible_moves =
-                    self.get_possible_moves(Position::new_from_idx(i).unwrap(), recursion_order);
-
-                // if piece.unwrap().piece_type == PieceType::Queen { eprintln!("Found moves for the queen are: {:?}", possible_moves); } // DEBUG
-                if possible_moves
-                    .iter() // Creates an iterable of positions.
-                    .any(|pos| pos == &king_pos)
-                // Checks if our position is equal to the list of possible moves. We use .any() since the objects may be different instances.
-                {
-                    // eprintln!("King is in check"); // DEBUG
-                    return true;
+                let pos = Position::new_from_idx(i).unwrap();
+                match piece.unwrap().piece_type {
+                    PieceType::King => (),
+                    PieceType::Knight => knights += 1,
+                    PieceType::Bishop => {
+                        if (pos.row + pos.col) % 2 == 1 {
+                            bishops_on_light_square += 1;
+                        } else {
+                            bishops_on_dark_square += 1;
+                        }
+                    }
+                    // A pawn, rook or queen is always enough to mate with
+                    PieceType::Queen | PieceType::Rook | PieceType::Pawn => return false,
                 }
-            } else {
-                // Do nothing
             }
         }
 
-        // If we have found no cases where the king is in check, the king is not in check.
-        return false;
+        if knights + bishops_on_light_square + bishops_on_dark_square <= 1 {
+            return true;
+        }
+        knights == 0 && (bishops_on_light_square == 0 || bishops_on_dark_square == 0)
+    }
+
+    /// Checks whether the king of colour `colour` is in check and returns a boolean.
+    /// This is done by checking whether the square of the king is attacked by the other colour, see `is_square_attacked`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS. If you are wondering whether the game is in state Check, please use `get_game_state` instead.
+    ///
+    /// Every enemy piece that attacks the king counts, even if it is pinned to its own king. A colour without a king is never in check.
+    fn is_in_check(&self, colour: Colour) -> bool {
+        match self.find_king_pos(colour) {
+            None => false,
+            Some(king_pos) => self.is_square_attacked(king_pos, colour.opposite()),
+        }
+    }
+
+    /// Checks whether the square at `pos` is attacked by some piece of colour `by` and returns a boolean.
+    /// This is done with the bitboards of the board, see `Bitboards`, by looking along the rows, columns and diagonals from the square
+    /// for queens, rooks and bishops, and at the squares a knight, king or pawn would attack it from.
+    ///
+    /// Note that this function does not generate any moves, so it does not recurse into `possible_moves`.
+    /// Whatever occupies `pos` is ignored, so a square counts as attacked even if it holds a piece of colour `by`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn is_square_attacked(&self, pos: Position, by: Colour) -> bool {
+        self.bitboards.is_square_attacked(pos.idx, by)
+    }
+
+    /// Returns every square attacked by some piece of colour `by`, ordered from a1 to h8 with a row at a time, e.g. for GUIs that highlight danger squares.
+    /// See `is_square_attacked`: pawns attack diagonally forwards but not the squares they can be pushed to,
+    /// and squares holding a piece of colour `by` count as attacked if another piece of that colour defends them.
+    pub fn attacked_squares(&self, by: Colour) -> Vec<Position> {
+        let mut squares: Vec<Position> = Vec::new();
+        for idx in 0..8 * 8 {
+            if self.bitboards.is_square_attacked(idx, by) {
+                // Unwrapping Position::new_from_idx(idx) is safe here since the board is well defined.
+                squares.push(Position::new_from_idx(idx).unwrap());
+            }
+        }
+        squares
     }
 
     /// Checks whether the colour of parameter `colour` has some legal move it can make and returns a boolean.
@@ -466,7 +1580,7 @@ impl Game {
             } else if piece.unwrap().colour == colour {
                 // Unwrapping piece is safe here since it is not none.
                 // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
-                let possible_moves = self.get_possible_moves(Position::new_from_idx(i).unwrap(), 0);
+                let possible_moves = self.possible_moves(Position::new_from_idx(i).unwrap());
                 // eprintln!("Possible moves found for piece {:?} are: {:?}", piece, possible_moves); // DEBUG
                 if possible_moves.len() > 0 {
                     // We have found at least one possible move and return true
@@ -479,614 +1593,4635 @@ impl Game {
         return false;
     }
 
-    /// Finds the king of colour `colour`'s position and returns it as a Position
+    /// Finds the king of colour `colour`'s position and returns it as a Position,
+    /// or `None` if the king is not on the board, e.g. while a position is set up after `empty`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn find_king_pos(&self, colour: Colour) -> Option<Position> {
+        self.find_pieces(PieceType::King, colour).first().copied()
+    }
+
+    /// Replaces the board with `board` and builds its bitboards.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn set_board(&mut self, board: [Option<Piece>; 8 * 8]) {
+        self.board = board;
+        self.bitboards = Bitboards::from_board(&board);
+    }
+
+    /// Places `piece` on the square at index `idx`, or empties the square if `piece` is `None`, keeping the bitboards up to date.
     ///
     /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
-    fn find_king_pos(&self, colour: Colour) -> Position {
+    fn set_square(&mut self, idx: usize, piece: Option<Piece>) {
+        self.board[idx] = piece;
+        self.bitboards.set_square(idx, piece);
+    }
+
+    /// Finds the positions of all pieces of type `piece_type` and colour `colour` and returns them as a list of Positions,
+    /// ordered from a1 to h8 with a row at a time, e.g. for AIs and endgame logic.
+    pub fn find_pieces(&self, piece_type: PieceType, colour: Colour) -> Vec<Position> {
+        let mut positions: Vec<Position> = Vec::new();
         for (i, piece) in self.board.iter().enumerate() {
-            if piece.is_none() {
-                // Do nothing
-            } else if piece.unwrap().piece_type == PieceType::King
-                && piece.unwrap().colour == colour
-            {
-                // Unwrapping piece is safe here since it is not none.
+            if *piece == Some(Piece { piece_type, colour }) {
                 // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
-                return Position::new_from_idx(i).unwrap();
+                positions.push(Position::new_from_idx(i).unwrap());
             }
         }
-        panic!("The king is not on the board! Something is wrong.");
+        positions
     }
 
     /// Set the piece type that a peasant becames following a promotion. Performs trimming and caps-handling.
+    /// The string is parsed by `PieceType::from_str`, after which the pawn is promoted by `set_promotion_piece`.
     ///
     /// Uses the field `last_moved_to` due to expected use of the library. Will break if used to promote a piece which was not just moved.
-    pub fn set_promotion(&mut self, piece: String) -> Result<GameState, String> {
+    pub fn set_promotion(&mut self, piece: String) -> Result<GameState, ChessError> {
+        let piece_type = PieceType::from_str(&piece)?;
+
+        self.set_promotion_piece(piece_type)
+    }
+
+    /// Promotes the pawn at `last_moved_to` to a piece of type `piece_type` and returns the resulting state of the game.
+    /// Should be called if the game is in GameState::WaitingOnPromotionChoice, like `set_promotion` but without having to stringify the choice.
+    ///
+    /// Returns an `Err(ChessError)` if the game is not waiting on a promotion or if `piece_type` is `King` or `Pawn`.
+    pub fn set_promotion_piece(&mut self, piece_type: PieceType) -> Result<GameState, ChessError> {
         if self.state != GameState::WaitingOnPromotionChoice {
-            return Err(String::from(format!(
-                "The game is not currently waiting on a promotion. Currently, the state is {:?}.",
-                self.state
-            )));
+            return Err(ChessError::NotPlayable(self.state));
         }
-        let piece_lowercase = piece.to_lowercase();
 
-        let piece_type = match piece_lowercase.trim() {
-            "queen" => PieceType::Queen,
-            "rook" => PieceType::Rook,
-            "bishop" => PieceType::Bishop,
-            "knight" => PieceType::Knight,
-            "king" => return Err(String::from("You can't promote a pawn to a king!")),
-            "pawn" => return Err(String::from("You can't promote a pawn to a pawn!")),
-            _ => {
-                return Err(String::from(format!(
-                    "Invalid input '{}'.",
-                    piece_lowercase
-                )))
-            }
-        };
+        match piece_type {
+            PieceType::King => return Err(ChessError::InvalidPromotion(String::from("You can't promote a pawn to a king!"))),
+            PieceType::Pawn => return Err(ChessError::InvalidPromotion(String::from("You can't promote a pawn to a pawn!"))),
+            _ => (),
+        }
 
-        self.board[self.last_moved_to.idx] = Some(Piece {
+        // the position with the unpromoted pawn was never completed, so it should not count towards a repetition
+        self.forget_position();
+        let promoted_piece = Some(Piece {
             piece_type,
             colour: self.board[self.last_moved_to.idx].unwrap().colour,
         });
+        self.set_square(self.last_moved_to.idx, promoted_piece);
 
-        // update active colour
-        if self.active_colour == Colour::Black {
-            self.active_colour = Colour::White;
-        } else {
-            self.active_colour = Colour::Black;
+        // the promotion is part of the last move, so it is saved in the same history entry
+        if let Some(entry) = self.history.last_mut() {
+            entry.promotion = Some(piece_type);
         }
 
+        // The active colour was already updated by make_move_pos when the pawn moved, so it should not be flipped again here.
+        self.record_position();
         self.update_game_state();
         return Ok(self.state);
     }
 
-    /// Get the current game state.
-    pub fn get_game_state(&self) -> GameState {
-        self.state
+    /// Sets the piece type that `make_move` automatically promotes pawns to, e.g. `Some(PieceType::Queen)` for quick games and AIs,
+    /// or `None` to wait on a promotion choice again, which is the default.
+    ///
+    /// Returns an `Err(ChessError)` if `piece_type` is `King` or `Pawn`, since pawns can't be promoted to those.
+    pub fn set_auto_promote(&mut self, piece_type: Option<PieceType>) -> Result<(), ChessError> {
+        match piece_type {
+            Some(PieceType::King) => return Err(ChessError::InvalidPromotion(String::from("You can't promote a pawn to a king!"))),
+            Some(PieceType::Pawn) => return Err(ChessError::InvalidPromotion(String::from("You can't promote a pawn to a pawn!"))),
+            _ => (),
+        }
+
+        self.auto_promote = piece_type;
+        Ok(())
     }
 
-    /// Get the current game state.
-    pub fn get_active_colour(&self) -> Colour {
-        self.active_colour
+    /// Places `piece` on `pos`, or empties `pos` if `piece` is `None`, e.g. for setting up a position on a board from `empty`.
+    ///
+    /// The resulting position is treated as a new starting position, so the move history is cleared and moves made before can no longer be taken back.
+    /// The halfmove clock and the fullmove number are reset to 0 and 1, as in the starting position.
+    /// The state of the game is recomputed as soon as there is exactly one king of each colour on the board.
+    pub fn place_piece(&mut self, pos: Position, piece: Option<Piece>) {
+        self.set_square(pos.idx, piece);
+        self.restart_from_position();
     }
 
-    pub fn get_board(&self) -> &[Option<Piece>; 8 * 8] {
-        return &self.board;
+    /// Sets the colour to move, e.g. for setting up a position on a board from `empty`. Like `place_piece`, this clears the move history and resets the halfmove clock and the fullmove number.
+    pub fn set_active_colour(&mut self, colour: Colour) {
+        self.active_colour = colour;
+        self.restart_from_position();
     }
 
-    /// If a piece is standing on the given tile, return all possible
-    /// new positions of that piece. Don't forget to the rules for check.
+    /// Takes back the last move, restoring the board, the active colour, the game state and the last moved position to what they were before it.
+    /// A move that promoted a pawn is taken back together with the promotion, as is a move still waiting on a promotion choice.
     ///
-    /// Takes the arguments `pos` of type Position and `recursion_order`. Put `recursion_order` to 0 if you do not know what you are doing.
-    /// `recursion_order` is an auxiliary variable that prevents the function from checking for potential Check-states further in the future than MAX_RECURSIONS.
-    ///
-    /// Note: en passent and castling not implemented. TODO.
-    pub fn get_possible_moves(&self, pos: Position, mut recursion_order: i32) -> Vec<Position> {
-        // Increment recursion_order. See docstring for details.
-        recursion_order += 1;
-
-        // Get piece. If it is None, it cannot move so return an empty vector.
-        let piece: Piece = match self.board[pos.idx] {
-            None => return vec![],
-            Some(piece) => piece,
+    /// Returns an `Err(ChessError)` if no moves have been made.
+    pub fn undo_move(&mut self) -> Result<(), ChessError> {
+        let entry = match self.history.pop() {
+            None => return Err(ChessError::NothingToUndo),
+            Some(entry) => entry,
         };
 
-        // Start listing possible moves.
-        let mut possible_moves: Vec<Position> = Vec::with_capacity(60);
+        self.forget_position();
+        self.board = entry.board;
+        self.bitboards = entry.bitboards;
+        self.active_colour = entry.active_colour;
+        self.state = entry.state;
+        self.last_moved_to = entry.last_moved_to;
+        self.halfmove_clock = entry.halfmove_clock;
+        self.fullmove_number = entry.fullmove_number;
+        self.result = entry.result;
+        self.draw_offer = entry.draw_offer;
 
-        // For each piece_type, follow some set of rules.
-        /* Design philosophy:
-            For every direction that a piece should move in, generate an offset or a set of offsets for that direction.
-            Then, iterate over every direction using the function try_move (see the function for details) which returns two booleans:
-                legal_move - if the move is legal; then it is added to the possible_moves vector
-                engine_should_continue - bool describing if the move should cause this method to halt further movement in the same direction
-            So, we iterate over each offset in every direction until we reach a point where engine_should_continue is false, and then we change direction.
+        Ok(())
+    }
 
-            Note that the pawn implementation is hacked! Pawns do not work the same way, but their behavior abides to the checks performed by the above booleans.
-            See their specific implementation for details.
+    /// Saves the full game, including the move history, as a string that `load_state` restores the game from.
+    /// Unlike a FEN string, the saved game can still take back the moves made before it was saved, and it remembers repeated positions.
+    ///
+    /// Like serialization with the `serde` feature, which this is built on, the clock is not saved.
+    #[cfg(feature = "serde")]
+    pub fn save_state(&self) -> String {
+        // Serializing a Game cannot fail, since all of its maps have keys that serialize as strings
+        serde_json::to_string(self).unwrap()
+    }
 
-            Note that trial.0 refers to legal_move and trial.1 refers to engine_should_continue.
-        */
-        match piece.piece_type {
-            PieceType::King => {
-                // Kings can move all directions but only one distance.
-                // See the comment above the match-case for details on the implementation.
-                for offset in [
-                    (1, 1),
-                    (1, 0),
-                    (1, -1),
-                    (0, 1),
-                    (0, -1),
-                    (-1, 1),
-                    (-1, 0),
-                    (-1, -1),
-                ] {
-                    let trial = self.try_move(pos, offset, recursion_order);
-                    if trial.0 {
-                        let mut ok_pos = pos.clone();
-                        ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                        possible_moves.push(ok_pos);
-                    }
-                }
-            }
-            PieceType::Queen => {
-                // Queens can move all directions and however far they like. (The board is size 8.)
-                // See the comment above the match-case for details on the implementation.
-                for dir in [
-                    (1, 1),
-                    (1, 0),
-                    (1, -1),
-                    (0, 1),
-                    (0, -1),
-                    (-1, 1),
-                    (-1, 0),
-                    (-1, -1),
-                ] {
-                    for len in 1..8 {
-                        let offset = (dir.0 * len, dir.1 * len);
-                        let trial = self.try_move(pos, offset, recursion_order);
-                        if trial.0 {
-                            let mut ok_pos = pos.clone();
-                            ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                            possible_moves.push(ok_pos);
-                        }
+    /// Restores a game saved by `save_state`, including its move history, such that `undo_move` can take back the moves made before saving.
+    ///
+    /// Returns an `Err(ChessError::ParseError)` if `s` is not a game saved by `save_state`.
+    #[cfg(feature = "serde")]
+    pub fn load_state(s: &str) -> Result<Game, ChessError> {
+        serde_json::from_str(s).map_err(|err| ChessError::ParseError(format!("Invalid saved game: {}", err)))
+    }
 
-                        if !trial.1 {
-                            break;
-                        }
-                    }
-                }
-            }
-            PieceType::Bishop => {
-                // Bishops can move all diagonal directions and however far they like. (The board is size 8.)
-                // See the comment above the match-case for details on the implementation.
-                for dir in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
-                    for len in 1..8 {
-                        let offset = (dir.0 * len, dir.1 * len);
-                        let trial = self.try_move(pos, offset, recursion_order);
-                        if trial.0 {
-                            let mut ok_pos = pos.clone();
-                            ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                            possible_moves.push(ok_pos);
-                        }
+    /// Makes the game timed by `clock`, or untimed if `clock` is `None`, which is the default.
+    /// The turn of the active colour is started, and from then on the clock is pressed by every move, see `make_move_pos`.
+    ///
+    /// Taking back moves does not give back the time spent on them. The clock is not kept when a game is serialized with the `serde` feature.
+    pub fn set_clock(&mut self, clock: Option<GameClock>) {
+        self.clock = clock;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.start_turn(self.active_colour);
+        }
+    }
 
-                        if !trial.1 {
-                            break;
-                        }
-                    }
-                }
+    /// Returns the clock of a timed game, or `None` if the game is untimed.
+    pub fn get_clock(&self) -> Option<&GameClock> {
+        self.clock.as_ref()
+    }
+
+    /// Lets `colour` resign, ending the game with GameState::GameOver and the opposite colour as the winner. Returns the resulting state of the game.
+    ///
+    /// If the game has already ended, nothing happens and the existing state is returned.
+    pub fn resign(&mut self, colour: Colour) -> GameState {
+        if self.state == GameState::GameOver || self.state == GameState::Draw {
+            return self.state;
+        }
+
+        self.state = GameState::GameOver;
+        self.result = Some(GameResult::Resignation(colour.opposite()));
+        self.state
+    }
+
+    /// Lets the active colour offer a draw. The offer stands until the opponent accepts it with `accept_draw` or declines it by making a move.
+    ///
+    /// If the game has already ended, nothing happens.
+    pub fn offer_draw(&mut self) {
+        if self.state == GameState::GameOver || self.state == GameState::Draw {
+            return;
+        }
+
+        self.draw_offer = Some(self.active_colour);
+    }
+
+    /// Accepts the pending draw offer, ending the game with GameState::Draw. Returns the resulting state of the game.
+    ///
+    /// Returns an `Err(ChessError)` if no draw offer is pending or if the game has already ended.
+    pub fn accept_draw(&mut self) -> Result<GameState, ChessError> {
+        if self.state == GameState::GameOver || self.state == GameState::Draw {
+            return Err(ChessError::NotPlayable(self.state));
+        }
+        if self.draw_offer.is_none() {
+            return Err(ChessError::NoDrawOffer);
+        }
+
+        self.draw_offer = None;
+        self.state = GameState::Draw;
+        self.result = Some(GameResult::DrawByAgreement);
+        Ok(self.state)
+    }
+
+    /// Claims a draw by threefold repetition for the active colour, ending the game with GameState::Draw if the current position
+    /// has occurred at least three times. Returns the resulting state of the game.
+    /// Unlike fivefold repetition, which draws the game by itself, threefold repetition only draws the game when claimed.
+    ///
+    /// Returns an `Err(ChessError)` if the game is not in progress or if the current position has occurred fewer than three times.
+    pub fn claim_threefold_repetition(&mut self) -> Result<GameState, ChessError> {
+        if !(self.state == GameState::InProgress || self.state == GameState::Check) {
+            return Err(ChessError::NotPlayable(self.state));
+        }
+        if self.position_occurrences() < Game::THREEFOLD_REPETITION_OCCURRENCES {
+            return Err(ChessError::DrawNotClaimable);
+        }
+
+        self.draw_offer = None;
+        self.state = GameState::Draw;
+        self.result = Some(GameResult::DrawByRule(DrawRule::ThreefoldRepetition));
+        Ok(self.state)
+    }
+
+    /// Claims a draw by the fifty-move rule for the active colour, ending the game with GameState::Draw if 50 moves by each player
+    /// have been made without a capture or a pawn move. Returns the resulting state of the game.
+    /// Unlike the seventy-five-move rule, which draws the game by itself, the fifty-move rule only draws the game when claimed.
+    ///
+    /// Returns an `Err(ChessError)` if the game is not in progress or if the halfmove clock has not reached 100, see `halfmove_clock`.
+    pub fn claim_fifty_move_draw(&mut self) -> Result<GameState, ChessError> {
+        if !(self.state == GameState::InProgress || self.state == GameState::Check) {
+            return Err(ChessError::NotPlayable(self.state));
+        }
+        if self.halfmove_clock < Game::FIFTY_MOVE_RULE_HALFMOVES {
+            return Err(ChessError::DrawNotClaimable);
+        }
+
+        self.draw_offer = None;
+        self.state = GameState::Draw;
+        self.result = Some(GameResult::DrawByRule(DrawRule::FiftyMoveRule));
+        Ok(self.state)
+    }
+
+    /// Makes a move from `from` to `to` and, if the move promotes a pawn, promotes it to `promotion` in the same call.
+    /// Intended for click-to-move interfaces where the user has selected a piece and a destination.
+    ///
+    /// Returns an error, and leaves the game untouched, if the move is illegal, if the move promotes a pawn but `promotion` is `None`,
+    /// or if `promotion` is supplied for a move that does not promote a pawn.
+    pub fn move_selected(
+        &mut self,
+        from: Position,
+        to: Position,
+        promotion: Option<PieceType>,
+    ) -> Result<GameState, ChessError> {
+        // We perform the move on a clone such that self is not modified if some step fails.
+        let mut game_after_movement = self.clone();
+        game_after_movement.make_move_pos(from, to)?;
+
+        // A move made out of time ends the game instead of being made, see make_move_pos
+        if let Some(GameResult::Timeout(_)) = game_after_movement.result {
+            *self = game_after_movement;
+            return Ok(self.state);
+        }
+
+        let waiting_on_promotion = game_after_movement.state == GameState::WaitingOnPromotionChoice;
+        match promotion {
+            None if waiting_on_promotion => {
+                return Err(ChessError::InvalidPromotion(String::from(
+                    "This move promotes a pawn, please supply the piece to promote it to.",
+                )))
             }
-            PieceType::Knight => {
-                // Knight can move according to eight movesets.
-                // See the comment above the match-case for details on the implementation.
-                for offset in [
-                    (2, 1),
-                    (2, -1),
-                    (1, 2),
-                    (1, -2),
-                    (-1, 2),
-                    (-1, -2),
-                    (-2, 1),
-                    (-2, -1),
-                ] {
-                    let trial = self.try_move(pos, offset, recursion_order);
-                    if trial.0 {
-                        let mut ok_pos = pos.clone();
-                        ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                        possible_moves.push(ok_pos);
-                    }
-                }
+            Some(piece_type) if waiting_on_promotion => {
+                game_after_movement.set_promotion_piece(piece_type)?;
             }
-            PieceType::Rook => {
-                // Rooks can move all non-diagonal directions and however far they like. (The board is size 8.)
-                // See the comment above the match-case for details on the implementation.
-                for dir in [(1, 0), (0, 1), (0, -1), (-1, 0)] {
-                    for len in 1..8 {
-                        let offset = (dir.0 * len, dir.1 * len);
-                        let trial = self.try_move(pos, offset, recursion_order);
-                        if trial.0 {
-                            let mut ok_pos = pos.clone();
-                            ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                            possible_moves.push(ok_pos);
-                        }
+            Some(_) => {
+                return Err(ChessError::InvalidPromotion(String::from(
+                    "A promotion was supplied, but this move does not promote a pawn.",
+                )))
+            }
+            None => (),
+        }
 
-                        if !trial.1 {
-                            break;
-                        }
-                    }
-                }
+        *self = game_after_movement;
+        Ok(self.state)
+    }
+
+    /// Makes the move `mv`, promoting a pawn to `mv.promotion` if the move promotes a pawn, and returns the resulting state of the game.
+    ///
+    /// Returns an error, and leaves the game untouched, under the same conditions as `move_selected`.
+    pub fn apply_move(&mut self, mv: Move) -> Result<GameState, ChessError> {
+        self.move_selected(mv.from, mv.to, mv.promotion)
+    }
+
+    /// Makes the move given in the coordinate notation of the Universal Chess Interface (UCI), e.g. `e2e4` or `e7e8q`,
+    /// and returns the resulting state of the game. See `Move::parse_uci` and `apply_move`.
+    ///
+    /// Returns an `Err(ChessError)` describing the error, and leaves the game untouched, if the move could not be parsed or made.
+    pub fn make_move_uci(&mut self, uci: &str) -> Result<GameState, ChessError> {
+        self.apply_move(Move::parse_uci(uci)?)
+    }
+
+    /// Makes the move given as a single string, with the positions either separated by whitespace, e.g. `e2 e4`, or not, e.g. `e2e4`,
+    /// and returns the resulting state of the game. A promotion piece may follow the destination, e.g. `e7 e8 q` or `e7e8q`.
+    ///
+    /// Without a promotion piece, the move is made by `make_move`, such that a piece set by `set_auto_promote` is respected.
+    /// Returns an `Err(ChessError)` describing the error if the move could not be parsed or made.
+    pub fn make_move_str(&mut self, mv: &str) -> Result<GameState, ChessError> {
+        let compact_mv: String = mv.split_whitespace().collect();
+        let parsed_mv = Move::parse_uci(&compact_mv)?;
+
+        if parsed_mv.promotion.is_none() {
+            // The move parsed, so the first four characters are the two positions
+            return self.make_move(&compact_mv[0..2], &compact_mv[2..4]);
+        }
+        self.apply_move(parsed_mv)
+    }
+
+    /// Makes the move given in Standard Algebraic Notation (SAN), e.g. `e4`, `Nf3`, `exd5`, `Nbd2` or `e8=Q`, and returns the resulting state of the game.
+    /// Check and annotation suffixes such as `+`, `#`, `!` and `?` are ignored.
+    ///
+    /// Returns an `Err(ChessError)` describing the error, and leaves the game untouched, if the SAN is malformed, if no legal move matches it,
+    /// if more than one legal move matches it, or if it is castling, which is not implemented.
+    pub fn make_move_san(&mut self, san: &str) -> Result<GameState, ChessError> {
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        if ["O-O", "O-O-O", "0-0", "0-0-0"].contains(&san) {
+            // Castling is not implemented, so it is never a legal move
+            return Err(ChessError::IllegalMove);
+        }
+
+        // The promotion is given after the destination, e.g. `e8=Q`
+        let (san, promotion) = match san.split_once('=') {
+            None => (san, None),
+            Some((rest, "Q")) => (rest, Some(PieceType::Queen)),
+            Some((rest, "R")) => (rest, Some(PieceType::Rook)),
+            Some((rest, "N")) => (rest, Some(PieceType::Knight)),
+            Some((rest, "B")) => (rest, Some(PieceType::Bishop)),
+            Some((_, piece)) => return Err(ChessError::ParseError(format!("Invalid promotion piece '{}' in SAN.", piece))),
+        };
+
+        // Pieces are given by an uppercase letter, pawns by none
+        let (piece_type, rest) = match san.chars().next() {
+            Some('K') => (PieceType::King, &san[1..]),
+            Some('Q') => (PieceType::Queen, &san[1..]),
+            Some('R') => (PieceType::Rook, &san[1..]),
+            Some('N') => (PieceType::Knight, &san[1..]),
+            Some('B') => (PieceType::Bishop, &san[1..]),
+            _ => (PieceType::Pawn, san),
+        };
+
+        // The destination is the last two characters, and anything before it is disambiguation and the capture marker
+        if rest.len() < 2 || !rest.is_ascii() {
+            return Err(ChessError::ParseError(format!("Invalid SAN '{}'.", san)));
+        }
+        let to = Position::parse_str(&rest[rest.len() - 2..])?;
+        let is_capture = rest.contains('x');
+        let mut from_col: Option<usize> = None;
+        let mut from_row: Option<usize> = None;
+        for c in rest[..rest.len() - 2].chars() {
+            match c {
+                'a'..='h' => from_col = Some(c as usize - 'a' as usize),
+                '1'..='8' => from_row = Some(c as usize - '1' as usize),
+                'x' => (),
+                _ => return Err(ChessError::ParseError(format!("Invalid SAN '{}'.", san))),
             }
-            PieceType::Pawn => {
-                /* This pawn-implementation is hacky :)
-                    We find the direction (positive or negative) and then iterate
-                    i) forward in that direction
-                    ii) to the sides
+        }
 
-                    In the forward direction we allow all moves which don't return a false boolean engine_should_continue from try_move (trial.1 in the code).
-                    This is because that indicates that we either i) have run into the end of the board or ii) have run into a piece.
-                    The first option isn't relevant for pawns, and the second the method try_move thinks is legal but actually isn't, since pawns can't capture forward.
+        // Unwrapping the piece at from is safe here since every legal move starts at a piece.
+        let candidates: Vec<(Position, Position)> = self
+            .all_legal_moves()
+            .into_iter()
+            .filter(|(from, candidate_to)| {
+                *candidate_to == to
+                    && self.board[from.idx].unwrap().piece_type == piece_type
+                    && (from_col.is_none() || from_col == Some(from.col))
+                    && (from_row.is_none() || from_row == Some(from.row))
+            })
+            .collect();
 
-                    For double-step-checking, we break the loop after the first iteration here if there is a piece on the way or if the piece is not on the second row.
+        if candidates.is_empty() {
+            return Err(ChessError::IllegalMove);
+        } else if candidates.len() > 1 {
+            return Err(ChessError::ParseError(format!("The move '{}' is ambiguous, please add the file or rank the piece moves from.", san)));
+        }
+        let (from, to) = candidates[0];
+        if is_capture && self.board[to.idx].is_none() {
+            return Err(ChessError::IllegalMove);
+        }
 
+        self.move_selected(from, to, promotion)
+    }
 
-                    In the diagonal direction we do the opposite! We ONLY allow moves for which try_move returns a false boolean engine_should_continue,
-                    with the same methodology. If engine_should_continue is false, we would be capturing a piece.
+    /// Get the current game state.
+    pub fn get_game_state(&self) -> GameState {
+        self.state
+    }
 
-                    See the docstring above the match-case for context.
-                */
+    /// Returns whether the king of the active colour is in check, including checkmate.
+    /// Returns false if the active colour has no king, e.g. while a position is set up after `empty`.
+    pub fn is_in_check_now(&self) -> bool {
+        self.is_in_check(self.active_colour)
+    }
 
-                let dir: i32;
-                let mut on_first_row = false;
-                if piece.colour == Colour::White {
-                    dir = 1;
-                    if pos.row == 1 {
-                        on_first_row = true;
-                    }
-                } else {
-                    dir = -1;
-                    if pos.row == 6 {
-                        on_first_row = true;
+    /// Returns whether the active colour is checkmated, i.e. in check without any legal move.
+    /// Returns false if the active colour has no king.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check(self.active_colour) && !self.can_make_legal_move(self.active_colour)
+    }
+
+    /// Returns whether the active colour is stalemated, i.e. not in check but without any legal move.
+    /// Returns false if the active colour has no king, since it then has no legal moves without being stalemated.
+    pub fn is_stalemate(&self) -> bool {
+        if self.find_king_pos(self.active_colour).is_none() {
+            return false;
+        }
+        !self.is_in_check(self.active_colour) && !self.can_make_legal_move(self.active_colour)
+    }
+
+    /// Returns the result of the game if it has ended, i.e. if the state is `GameOver` or `Draw`, else `None`.
+    pub fn game_result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    /// Returns the colour that has offered a draw, if a draw offer is pending.
+    pub fn get_draw_offer(&self) -> Option<Colour> {
+        self.draw_offer
+    }
+
+    /// Returns the number of the current full move. It starts at 1 and increases after every move by Black.
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    /// Returns the number of halfmoves since the last capture or pawn move, as used by the fifty-move and seventy-five-move rules.
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// Get the current game state.
+    pub fn get_active_colour(&self) -> Colour {
+        self.active_colour
+    }
+
+    pub fn get_board(&self) -> &[Option<Piece>; 8 * 8] {
+        return &self.board;
+    }
+
+    /// Returns the piece at `pos`, or `None` if the square is empty.
+    pub fn get_piece_at(&self, pos: Position) -> Option<Piece> {
+        self.board[pos.idx]
+    }
+
+    /// Returns the board as an array of compact piece codes, e.g. for FFI or for cheap snapshots.
+    ///
+    /// Empty squares are coded as 0. White pieces are coded as 1-6 in the order king, queen, rook, knight, bishop, pawn,
+    /// and black pieces as 7-12 in the same order.
+    pub fn piece_codes(&self) -> [u8; 8 * 8] {
+        let mut codes = [0; 8 * 8];
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing, the square is already coded as empty
+            } else {
+                // Unwrapping piece is safe here since it is not none.
+                let type_code = match piece.unwrap().piece_type {
+                    PieceType::King => 1,
+                    PieceType::Queen => 2,
+                    PieceType::Rook => 3,
+                    PieceType::Knight => 4,
+                    PieceType::Bishop => 5,
+                    PieceType::Pawn => 6,
+                };
+                codes[i] = match piece.unwrap().colour {
+                    Colour::White => type_code,
+                    Colour::Black => type_code + 6,
+                };
+            }
+        }
+
+        codes
+    }
+
+    /// Returns the number of pieces remaining on the board, of both colours.
+    pub fn piece_count(&self) -> usize {
+        self.board.iter().filter(|piece| piece.is_some()).count()
+    }
+
+    /// Returns the number of pieces of colour `colour` remaining on the board.
+    pub fn piece_count_by_colour(&self, colour: Colour) -> usize {
+        self
+            .board
+            .iter()
+            .filter(|piece| piece.is_some() && piece.unwrap().colour == colour)
+            .count()
+    }
+
+    /// Returns whether the current position is legal to start a game from. A position is legal if
+    /// - each colour has exactly one king,
+    /// - there are no pawns on the first or last row, and
+    /// - the colour that is not to move is not in check.
+    ///
+    /// Castling and en passant are not implemented, so there are no such rights to check for consistency.
+    pub fn is_legal_position(&self) -> bool {
+        let mut white_kings = 0;
+        let mut black_kings = 0;
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing
+            } else {
+                // Unwrapping piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let piece = piece.unwrap();
+                let pos = Position::new_from_idx(i).unwrap();
+                if piece.piece_type == PieceType::King {
+                    match piece.colour {
+                        Colour::White => white_kings += 1,
+                        Colour::Black => black_kings += 1,
                     }
+                } else if piece.piece_type == PieceType::Pawn && (pos.row == 0 || pos.row == 7) {
+                    return false;
                 }
+            }
+        }
 
-                // forward direction
-                for (i, j) in [(1, 0), (2, 0)] {
-                    let offset: (i32, i32) = (i * dir, j);
-                    let trial = self.try_move(pos, offset, recursion_order);
-                    if trial.0 && trial.1 {
-                        let mut ok_pos = pos.clone();
-                        ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                        possible_moves.push(ok_pos);
-                    }
-                    if !on_first_row || !trial.1 {
-                        // break if it is not on the first row or if there was a piece in the way
-                        break;
-                    }
+        if white_kings != 1 || black_kings != 1 {
+            return false;
+        }
+
+        // The colour that is not to move cannot be in check, since that means that the king could be captured
+        !self.is_in_check(self.active_colour.opposite())
+    }
+
+    /// Returns false if `colour` does not have the material to checkmate against any defense, else true.
+    /// This is the case if `colour` has only its king left, or its king and a single knight or bishop.
+    ///
+    /// Useful for suggesting a draw offer, since a side that cannot win has nothing to play for.
+    pub fn can_win(&self, colour: Colour) -> bool {
+        !self.cannot_mate(colour)
+    }
+
+    /// Returns true if `colour` lacks the material to ever deliver checkmate, that is a lone king, or a king and a single knight or bishop.
+    ///
+    /// Unlike a dead position, this only concerns `colour`. The opponent may still be able to win.
+    pub fn cannot_mate(&self, colour: Colour) -> bool {
+        let mut minor_pieces = 0;
+
+        for piece in self.board.iter() {
+            if piece.is_none() {
+                // Do nothing
+            } else if piece.unwrap().colour == colour {
+                // Unwrapping piece is safe here since it is not none.
+                match piece.unwrap().piece_type {
+                    PieceType::King => (),
+                    PieceType::Knight | PieceType::Bishop => minor_pieces += 1,
+                    // A single pawn, rook or queen is enough to mate with
+                    PieceType::Queen | PieceType::Rook | PieceType::Pawn => return false,
                 }
+            }
+        }
 
-                // diagonal direction
-                for (i, j) in [(1, 1), (1, -1)] {
-                    let offset: (i32, i32) = (i * dir, j);
-                    let trial = self.try_move(pos, offset, recursion_order);
-                    if trial.0 && !trial.1 {
-                        let mut ok_pos = pos.clone();
-                        ok_pos.offset_self(offset).unwrap(); // unwrap is safe after try_move
-                        possible_moves.push(ok_pos);
-                    }
+        minor_pieces <= 1
+    }
+
+    /// Returns the position of each pawn of colour `colour` together with the number of rows it needs to advance to be promoted.
+    /// White pawns advance towards row 8 and black pawns towards row 1.
+    pub fn pawn_promotion_distances(&self, colour: Colour) -> Vec<(Position, usize)> {
+        let mut distances: Vec<(Position, usize)> = Vec::new();
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing
+            } else if piece.unwrap().piece_type == PieceType::Pawn && piece.unwrap().colour == colour {
+                // Unwrapping piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let pos = Position::new_from_idx(i).unwrap();
+                let distance = match colour {
+                    Colour::White => 7 - pos.row,
+                    Colour::Black => pos.row,
+                };
+                distances.push((pos, distance));
+            }
+        }
+
+        distances
+    }
+
+    /// Returns whether the king at `king` can catch the pawn of colour `pawn_colour` at `pawn` before it promotes,
+    /// according to the rule of the square. Only the geometry is considered: other pieces on the board are ignored.
+    ///
+    /// The square of the pawn contains every position within as many king moves of the promotion square as the pawn needs to promote.
+    /// If the active colour is the king's, the king catches the pawn if it can step into the square. If the active colour is the pawn's,
+    /// the king must already be inside the square. A pawn on its first row is counted as one move closer due to its double step.
+    pub fn king_can_catch_pawn(&self, king: Position, pawn: Position, pawn_colour: Colour) -> bool {
+        let (promotion_row, first_row): (usize, usize) = match pawn_colour {
+            Colour::White => (7, 1),
+            Colour::Black => (0, 6),
+        };
+
+        let mut pawn_moves = (promotion_row as i32 - pawn.row as i32).abs();
+        if pawn.row == first_row {
+            pawn_moves -= 1;
+        }
+
+        // The number of king moves needed to reach the promotion square
+        let king_moves = std::cmp::max(
+            (promotion_row as i32 - king.row as i32).abs(),
+            (pawn.col as i32 - king.col as i32).abs(),
+        );
+
+        if self.active_colour == pawn_colour {
+            king_moves <= pawn_moves
+        } else {
+            king_moves <= pawn_moves + 1
+        }
+    }
+
+    /// Returns the ECO code of the opening that the current position belongs to, or `None` if the position is not in the built-in table.
+    ///
+    /// The lookup is done by position rather than by move order: every line in the table is replayed from the starting position
+    /// and compared to the current board and active colour. This means that transpositions are recognised, but also that the
+    /// code is only returned while the game is exactly in the position of some line.
+    pub fn eco_code(&self) -> Option<&'static str> {
+        for (code, line) in Game::OPENINGS.iter() {
+            let moves: Vec<&str> = line.split_whitespace().collect();
+
+            let mut game = Game::new();
+            for i in 0..(moves.len() / 2) {
+                // unwrap is safe since the table only contains legal lines
+                game.make_move(moves[2 * i], moves[2 * i + 1]).unwrap();
+            }
+
+            if game.board == self.board && game.active_colour == self.active_colour {
+                return Some(code);
+            }
+        }
+
+        None
+    }
+
+    /// Returns true if `colour` has at least two bishops standing on squares of opposite colours, a.k.a. the bishop pair.
+    ///
+    /// The colour of a square is given by `(row + col) % 2`.
+    pub fn has_bishop_pair(&self, colour: Colour) -> bool {
+        let mut on_dark_square = false;
+        let mut on_light_square = false;
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing
+            } else if piece.unwrap().piece_type == PieceType::Bishop && piece.unwrap().colour == colour {
+                // Unwrapping piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let pos = Position::new_from_idx(i).unwrap();
+                if (pos.row + pos.col) % 2 == 1 {
+                    on_light_square = true;
+                } else {
+                    on_dark_square = true;
                 }
             }
         }
-        return possible_moves;
+
+        on_dark_square && on_light_square
     }
 
-    /// This function tries to move a piece from old_pos to the offset (i32, i32). Does not check whether pieces are in the way for this move, but it does
-    /// check whether it puts the own king in check.
-    /// Takes as input `recursion_order` too, which is an integer describing which order in the recursion this iteration of try_move is.
-    /// If the iteration is higher than MAX_RECURSIONS, this function will not check whether a move implies putting the king in check.
+    /// If a piece is standing on the given tile, return all possible
+    /// new positions of that piece. Don't forget to the rules for check.
     ///
-    /// Returns two booleans, one bool indicating whether the move was legal (internally legal_move)
-    /// and another bool indicating whether the engine should continue checking for legal moves in the same direction (internally engine_should_continue)
+    /// Takes the arguments `pos` of type Position and `recursion_order`.
+    /// `recursion_order` is deprecated and ignored, since checking whether a move puts the own king in check no longer recurses into this function.
+    /// It is only kept such that existing callers keep working, so please use `possible_moves` instead.
     ///
-    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
-    fn try_move(
-        &self,
-        old_pos: Position,
-        offset: (i32, i32),
-        recursion_order: i32,
-    ) -> (bool, bool) {
+    /// Note: en passent and castling not implemented. TODO.
+    pub fn get_possible_moves(&self, pos: Position, _recursion_order: i32) -> Vec<Position> {
+        self.possible_moves(pos)
+    }
+
+    /// If a piece is standing on the given tile, return all possible
+    /// new positions of that piece, leaving out the moves that would put the own king in check.
+    ///
+    /// ```
+    /// use chess_template::{Game, Position};
+    ///
+    /// let game = Game::new();
+    /// let moves = game.possible_moves(Position::parse_str("g1").unwrap());
+    /// assert_eq!(moves.len(), 2);
+    /// ```
+    ///
+    /// A piece whose colour has no king on the board, e.g. while a position is set up after `empty`, has no legal moves.
+    ///
+    /// Note: en passent and castling not implemented. TODO.
+    pub fn possible_moves(&self, pos: Position) -> Vec<Position> {
+        let piece = match self.board[pos.idx] {
+            None => return vec![],
+            Some(piece) => piece,
+        };
+
+        // Without a king, there is no king to keep out of check
+        if self.bitboards.pieces[Bitboards::index(PieceType::King, piece.colour)] == 0 {
+            return vec![];
+        }
+
+        let mut possible_moves = self.pseudo_legal_moves(pos);
+        possible_moves.retain(|to| self.keeps_king_safe(pos, *to));
+        possible_moves
+    }
+
+    /// Returns the same moves as `possible_moves`, split into a tuple of (captures, quiet moves), e.g. for move ordering in a search.
+    /// A move is a capture if its destination holds an enemy piece. En passant is not implemented, so there are no other captures.
+    pub fn possible_moves_categorized(&self, pos: Position) -> (Vec<Position>, Vec<Position>) {
+        // Legal moves never end on a piece of the own colour, so any piece at the destination is an enemy piece.
+        self
+            .possible_moves(pos)
+            .into_iter()
+            .partition(|to| self.board[to.idx].is_some())
+    }
+
+    /// Checks whether moving the piece at `from` to `to` leaves the own king out of check and returns a boolean.
+    /// This is done by performing the move on a copy of the bitboards of the board, rather than on a clone of the whole game.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn keeps_king_safe(&self, from: Position, to: Position) -> bool {
+        // Unwrapping is safe since the moves are generated from the piece at `from`.
+        let piece = self.board[from.idx].unwrap();
+        let king_pos = if piece.piece_type == PieceType::King {
+            to
+        } else {
+            match self.find_king_pos(piece.colour) {
+                // Without a king, there is no king to keep out of check. `possible_moves` returns no moves in that case anyway.
+                None => return true,
+                Some(king_pos) => king_pos,
+            }
+        };
+
+        let mut bitboards_after_movement = self.bitboards;
+        bitboards_after_movement.move_piece(from.idx, to.idx);
+        !bitboards_after_movement.is_square_attacked(king_pos.idx, piece.colour.opposite())
+    }
+
+    /// If a piece is standing on the given tile, return all new positions that the piece could move to, regardless of whether the moves put the own king in check.
+    /// Used by `possible_moves`, which leaves out the moves that do, and directly wherever an illegal position is fine, e.g. to find the squares a piece attacks.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn pseudo_legal_moves(&self, pos: Position) -> Vec<Position> {
+        // Get piece. If it is None, it cannot move so return an empty vector.
+        let piece: Piece = match self.board[pos.idx] {
+            None => return vec![],
+            Some(piece) => piece,
+        };
+
+        // Start listing possible moves.
+        let mut possible_moves: Vec<Position> = Vec::with_capacity(60);
+
+        // For each piece_type, follow some set of rules.
+        /* Design philosophy:
+            For every direction that a piece should move in, generate an offset or a set of offsets for that direction.
+            Then, iterate over every direction using the function try_move (see the function for details) which returns two booleans:
+                legal_move - if the move is legal; then it is added to the possible_moves vector
+                engine_should_continue - bool describing if the move should cause this method to halt further movement in the same direction
+            So, we iterate over each offset in every direction until we reach a point where engine_should_continue is false, and then we change direction.
+
+            Note that the pawn implementation is hacked! Pawns do not work the same way, but their behavior abides to the checks performed by the above booleans.
+            See their specific implementation for details.
+
+            Note that trial.0 refers to legal_move and trial.1 refers to engine_should_continue.
+        */
+        match piece.piece_type {
+            PieceType::King => {
+                // Kings can move all directions but only one distance.
+                // See the comment above the match-case for details on the implementation.
+                for offset in [
+                    (1, 1),
+                    (1, 0),
+                    (1, -1),
+                    (0, 1),
+                    (0, -1),
+                    (-1, 1),
+                    (-1, 0),
+                    (-1, -1),
+                ] {
+                    let trial = self.try_move(pos, offset);
+                    if trial.0 {
+                        possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                    }
+                }
+            }
+            PieceType::Queen => {
+                // Queens can move all directions and however far they like. (The board is size 8.)
+                // See the comment above the match-case for details on the implementation.
+                for dir in [
+                    (1, 1),
+                    (1, 0),
+                    (1, -1),
+                    (0, 1),
+                    (0, -1),
+                    (-1, 1),
+                    (-1, 0),
+                    (-1, -1),
+                ] {
+                    for len in 1..8 {
+                        let offset = (dir.0 * len, dir.1 * len);
+                        let trial = self.try_move(pos, offset);
+                        if trial.0 {
+                            possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                        }
+
+                        if !trial.1 {
+                            break;
+                        }
+                    }
+                }
+            }
+            PieceType::Bishop => {
+                // Bishops can move all diagonal directions and however far they like. (The board is size 8.)
+                // See the comment above the match-case for details on the implementation.
+                for dir in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                    for len in 1..8 {
+                        let offset = (dir.0 * len, dir.1 * len);
+                        let trial = self.try_move(pos, offset);
+                        if trial.0 {
+                            possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                        }
+
+                        if !trial.1 {
+                            break;
+                        }
+                    }
+                }
+            }
+            PieceType::Knight => {
+                // Knight can move according to eight movesets.
+                // See the comment above the match-case for details on the implementation.
+                for offset in [
+                    (2, 1),
+                    (2, -1),
+                    (1, 2),
+                    (1, -2),
+                    (-1, 2),
+                    (-1, -2),
+                    (-2, 1),
+                    (-2, -1),
+                ] {
+                    let trial = self.try_move(pos, offset);
+                    if trial.0 {
+                        possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                    }
+                }
+            }
+            PieceType::Rook => {
+                // Rooks can move all non-diagonal directions and however far they like. (The board is size 8.)
+                // See the comment above the match-case for details on the implementation.
+                for dir in [(1, 0), (0, 1), (0, -1), (-1, 0)] {
+                    for len in 1..8 {
+                        let offset = (dir.0 * len, dir.1 * len);
+                        let trial = self.try_move(pos, offset);
+                        if trial.0 {
+                            possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                        }
+
+                        if !trial.1 {
+                            break;
+                        }
+                    }
+                }
+            }
+            PieceType::Pawn => {
+                /* This pawn-implementation is hacky :)
+                    We find the direction (positive or negative) and then iterate
+                    i) forward in that direction
+                    ii) to the sides
+
+                    In the forward direction we allow all moves which don't return a false boolean engine_should_continue from try_move (trial.1 in the code).
+                    This is because that indicates that we either i) have run into the end of the board or ii) have run into a piece.
+                    The first option isn't relevant for pawns, and the second the method try_move thinks is legal but actually isn't, since pawns can't capture forward.
+
+                    For double-step-checking, we break the loop after the first iteration here if there is a piece on the way or if the piece is not on the second row.
+
+
+                    In the diagonal direction we do the opposite! We ONLY allow moves for which try_move returns a false boolean engine_should_continue,
+                    with the same methodology. If engine_should_continue is false, we would be capturing a piece.
+
+                    See the docstring above the match-case for context.
+                */
+
+                // Pawns can never stand on the first or last row in a legal game, since they are promoted on the last row.
+                // A misplaced pawn found there anyway, e.g. on a custom board, is not given any moves.
+                if pos.row == 0 || pos.row == 7 {
+                    return possible_moves;
+                }
+
+                let dir: i32;
+                let mut on_first_row = false;
+                if piece.colour == Colour::White {
+                    dir = 1;
+                    if pos.row == 1 {
+                        on_first_row = true;
+                    }
+                } else {
+                    dir = -1;
+                    if pos.row == 6 {
+                        on_first_row = true;
+                    }
+                }
+
+                // forward direction
+                for (i, j) in [(1, 0), (2, 0)] {
+                    let offset: (i32, i32) = (i * dir, j);
+                    let trial = self.try_move(pos, offset);
+                    if trial.0 && trial.1 {
+                        possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                    }
+                    if !on_first_row || !trial.1 {
+                        // break if it is not on the first row or if there was a piece in the way
+                        break;
+                    }
+                }
+
+                // diagonal direction
+                for (i, j) in [(1, 1), (1, -1)] {
+                    let offset: (i32, i32) = (i * dir, j);
+                    let trial = self.try_move(pos, offset);
+                    if trial.0 && !trial.1 {
+                        possible_moves.push(pos.offset(offset).unwrap()); // unwrap is safe after try_move
+                    }
+                }
+            }
+        }
+        return possible_moves;
+    }
+
+    /// Returns the moves that `piece` would have if it was standing on `pos`, without modifying the board.
+    /// Intended for "what if" analysis such as tutorials and placement puzzles, or for move previews in a UI,
+    /// where `get_possible_moves` returns no moves for an empty square.
+    ///
+    /// Since the hypothetical position may itself be illegal, the moves are not checked for whether they put the own king in check.
+    /// If `pos` is not empty, no piece can be placed there and an empty vector is returned.
+    pub fn hypothetical_moves(&self, piece: Piece, pos: Position) -> Vec<Position> {
+        if self.board[pos.idx].is_some() {
+            return vec![];
+        }
+
+        let mut game_with_piece = self.clone();
+        game_with_piece.set_square(pos.idx, Some(piece));
+
+        game_with_piece.pseudo_legal_moves(pos)
+    }
+
+    /// Returns whether moving from `from` to `to` is a legal move for the active colour.
+    ///
+    /// Only the moves of the piece at `from` are generated, which makes this much cheaper than generating every legal move
+    /// when validating a single candidate move.
+    pub fn is_move_legal(&self, from: Position, to: Position) -> bool {
+        if !(self.state == GameState::InProgress || self.state == GameState::Check) {
+            return false;
+        }
+
+        match self.board[from.idx] {
+            None => return false,
+            Some(piece) => {
+                if piece.colour != self.active_colour {
+                    return false;
+                }
+            }
+        }
+
+        self.possible_moves(from).contains(&to)
+    }
+
+    /// Returns whether moving the piece at `from` to `to` promotes a pawn, i.e. whether the piece is a pawn that would reach the last row.
+    /// Does not check whether the move is legal.
+    pub fn is_promotion_move(&self, from: Position, to: Position) -> bool {
+        match self.board[from.idx] {
+            Some(Piece { piece_type: PieceType::Pawn, colour: Colour::White }) => to.row == 7,
+            Some(Piece { piece_type: PieceType::Pawn, colour: Colour::Black }) => to.row == 0,
+            _ => false,
+        }
+    }
+
+    /// Returns every legal move of the piece at `pos` as a list of Moves, which can be made by `apply_move`.
+    ///
+    /// Unlike `possible_moves`, the promotions are explicit: a move that promotes a pawn is listed once for every piece type
+    /// that the pawn can be promoted to, and every other move is listed once without a promotion.
+    pub fn legal_moves_from(&self, pos: Position) -> Vec<Move> {
+        let mut moves: Vec<Move> = Vec::new();
+        for to in self.possible_moves(pos) {
+            if self.is_promotion_move(pos, to) {
+                for piece_type in [PieceType::Queen, PieceType::Rook, PieceType::Bishop, PieceType::Knight] {
+                    moves.push(Move::new_promotion(pos, to, piece_type));
+                }
+            } else {
+                moves.push(Move::new(pos, to));
+            }
+        }
+        moves
+    }
+
+    /// Returns the positions of the pieces of colour `colour` that can move to `target`, ordered from a1 to h8 with a row at a time,
+    /// e.g. for "which piece can go here" interactions. This is the inverse lookup of `possible_moves`.
+    ///
+    /// The moves are legal for `colour` regardless of whose turn it is.
+    pub fn movers_to(&self, target: Position, colour: Colour) -> Vec<Position> {
+        let mut movers: Vec<Position> = Vec::new();
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing
+            } else if piece.unwrap().colour == colour {
+                // Unwrapping piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let pos = Position::new_from_idx(i).unwrap();
+                if self.possible_moves(pos).contains(&target) {
+                    movers.push(pos);
+                }
+            }
+        }
+        movers
+    }
+
+    /// Returns every legal move of the active colour whose destination is one of `targets`, as a list of (from, to)-pairs.
+    /// Useful for hint systems, e.g. to show all moves to the center of the board.
+    pub fn legal_moves_into(&self, targets: &[Position]) -> Vec<(Position, Position)> {
+        self
+            .all_legal_moves()
+            .into_iter()
+            .filter(|(_, to)| targets.contains(to))
+            .collect()
+    }
+
+    /// Returns every legal move of the active colour that captures a piece, as a list of (from, to)-pairs.
+    pub fn legal_captures(&self) -> Vec<(Position, Position)> {
+        self
+            .all_legal_moves()
+            .into_iter()
+            .filter(|(_, to)| self.board[to.idx].is_some())
+            .collect()
+    }
+
+    /// Returns every legal move of the active colour that does not capture a piece, as a list of (from, to)-pairs.
+    /// Together with `legal_captures` this partitions the legal moves of the active colour.
+    pub fn legal_quiet_moves(&self) -> Vec<(Position, Position)> {
+        self
+            .all_legal_moves()
+            .into_iter()
+            .filter(|(_, to)| self.board[to.idx].is_none())
+            .collect()
+    }
+
+    /// Returns every legal move of the active colour after which the moved piece attacks `target`, as a list of (from, to)-pairs.
+    /// If `target` holds a piece of the active colour, these are the moves that defend it such that a capture there could be recaptured.
+    ///
+    /// Moves by a piece standing on `target` and moves to `target` are not included. Pawns reaching the last row are assumed to be promoted to queens.
+    pub fn moves_defending(&self, target: Position) -> Vec<(Position, Position)> {
+        let mut moves: Vec<(Position, Position)> = Vec::new();
+
+        for (from, to) in self.all_legal_moves() {
+            if from == target || to == target {
+                continue;
+            }
+
+            // Unwrapping is safe since the legal moves are generated from the piece at `from`.
+            let mut piece = self.board[from.idx].unwrap();
+            if piece.piece_type == PieceType::Pawn && (to.row == 0 || to.row == 7) {
+                piece.piece_type = PieceType::Queen;
+            }
+
+            // The move is only made on a copy of the bitboards, rather than on a clone of the whole game
+            let mut bitboards_after_movement = self.bitboards;
+            bitboards_after_movement.move_piece(from.idx, to.idx);
+            if bitboards_after_movement.attacks(piece, to.idx, target.idx) {
+                moves.push((from, to));
+            }
+        }
+
+        moves
+    }
+
+    /// Returns the checks, captures and threats (CCT) of the active colour as a list of (from, to)-pairs, ordered by importance.
+    /// These are the forcing moves that a tactical search should look at first.
+    ///
+    /// Checks come first, then captures ordered by the value of the captured piece, and last the moves after which the moved piece
+    /// attacks an enemy piece that is worth more than itself or that is undefended. Quiet moves that threaten nothing are not included.
+    pub fn cct_moves(&self) -> Vec<(Position, Position)> {
+        let mut checks: Vec<(Position, Position)> = Vec::new();
+        let mut captures: Vec<(Position, Position)> = Vec::new();
+        let mut threats: Vec<(Position, Position)> = Vec::new();
+
+        for (from, to) in self.all_legal_moves() {
+            let game_after_movement = self.clone_with_move(from, to);
+
+            if game_after_movement.is_in_check(self.active_colour.opposite()) {
+                checks.push((from, to));
+            } else if self.board[to.idx].is_some() {
+                captures.push((from, to));
+            } else if game_after_movement.threatens_material(to) {
+                threats.push((from, to));
+            }
+        }
+
+        // Unwrapping is safe here since every capture has a piece on its target.
+        captures.sort_by_key(|(_, to)| -self.board[to.idx].unwrap().piece_type.value());
+
+        checks.append(&mut captures);
+        checks.append(&mut threats);
+        checks
+    }
+
+    /// Returns whether the piece at `pos` attacks an enemy piece that is either worth more than itself or not defended by any other enemy piece.
+    /// The enemy king is not counted, since attacking it is a check.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn threatens_material(&self, pos: Position) -> bool {
+        let piece = match self.board[pos.idx] {
+            None => return false,
+            Some(piece) => piece,
+        };
+
+        for (i, target_piece) in self.board.iter().enumerate() {
+            if target_piece.is_none() {
+                // Do nothing
+            } else if target_piece.unwrap().colour != piece.colour
+                && target_piece.unwrap().piece_type != PieceType::King
+            {
+                // Unwrapping target_piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let target = Position::new_from_idx(i).unwrap();
+                if !self.attacks(pos, target) {
+                    continue;
+                }
+                if target_piece.unwrap().piece_type.value() > piece.piece_type.value() {
+                    return true;
+                }
+
+                // A piece never attacks its own square, so the target is defended if its square is attacked by its own colour
+                let defended = self.bitboards.is_square_attacked(i, target_piece.unwrap().colour);
+                if !defended {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Returns whether the piece at `from` attacks the position `target`, regardless of what stands on `target`.
+    /// This is done with the bitboards of the board, see `Bitboards::attacks`, so the game is not cloned.
+    /// An attack does not need to be a legal move, so it does not matter whether capturing on `target` would put the own king in check.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn attacks(&self, from: Position, target: Position) -> bool {
+        let piece = match self.board[from.idx] {
+            None => return false,
+            Some(piece) => piece,
+        };
+        if from == target {
+            return false;
+        }
+
+        self.bitboards.attacks(piece, from.idx, target.idx)
+    }
+
+    /// This function tries to move a piece from old_pos to the offset (i32, i32). Does not check whether pieces are in the way for this move,
+    /// nor whether it puts the own king in check, e.g. by moving a king next to the enemy king, which is left to `possible_moves`.
+    /// A king thus attacks the squares next to the enemy king, such that it can defend pieces standing there.
+    ///
+    /// Returns two booleans, one bool indicating whether the move was legal (internally legal_move)
+    /// and another bool indicating whether the engine should continue checking for legal moves in the same direction (internally engine_should_continue)
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn try_move(&self, old_pos: Position, offset: (i32, i32)) -> (bool, bool) {
         if self.board[old_pos.idx].is_none() {
             panic!(
                 "try_move was called trying to move a piece from a tile where there is no piece!"
             );
         }
 
-        /* The philosophy for this function is that we generate a clone of the own game, perform the move in that game and see where that takes us.
-            We also perform error-handling for the offset (if it is off the board) and check whether there is a piece in the way.
-            If there is a piece in the way, we check that it is of the opposite color (a.k.a. capture-able)
-            and in that case return that the engine should not continue.
+        /* The philosophy for this function is that we perform error-handling for the offset (if it is off the board) and check whether there is a piece in the way.
+            If there is a piece in the way, we check that it is of the opposite color (a.k.a. capture-able)
+            and in that case return that the engine should not continue.
+
+            There are comments guiding you through the if-clauses below if you need to read the code.
+        */
+
+        // Unwrapping is safe since it is not none.
+        let player_colour = self.board[old_pos.idx].unwrap().colour;
+
+        // Generate new position and check if it is in the board
+        let new_pos = match old_pos.offset(offset) {
+            Err(_) => return (false, false), // If the new position is outside of the board, it is not valid and the engine should change direction.
+            Ok(new_pos) => new_pos,          // continue
+        };
+
+        // eprintln!("Trying to move {:?} from {:?} to {:?}", self.board[old_pos.idx], old_pos, new_pos); // DEBUG
+
+        // Check piece movement on the board
+        let legal_move: bool;
+        let engine_should_continue: bool;
+        match self.board[new_pos.idx] {
+            // If there is no piece in the new slot, the move is legal and the engine should keep checking the same direction.
+            None => {
+                engine_should_continue = true;
+                legal_move = true;
+            }
+            // If there is a piece in the new slot, the engine should not keep checking the same direction...
+            Some(piece) => {
+                engine_should_continue = false;
+                // ... and the move is legal only if the piece is of the opposite colour
+                legal_move = piece.colour != player_colour;
+            }
+        }
+
+        // eprintln!("Legal? {}. Engine should continue? {}", legal_move, engine_should_continue); // DEBUG
+        return (legal_move, engine_should_continue);
+    }
+}
+
+/// Here we implement the search functions of our game, used by the AI.
+impl Game {
+    /// Searches for the best move for the active colour while visiting at most `max_nodes` positions.
+    ///
+    /// The search deepens one ply at a time and returns the best move of the deepest completed search together with the number of
+    /// positions visited. If the limit is reached before a single ply has been searched, some legal move is still returned.
+    /// Returns `(None, 0)` if the active colour has no legal moves.
+    pub fn best_move_with_limit(&self, max_nodes: u64) -> (Option<(Position, Position)>, u64) {
+        let moves = self.all_legal_moves();
+        if moves.is_empty() {
+            return (None, 0);
+        }
+
+        let mut nodes: u64 = 0;
+        // Fall back on the first legal move such that a tiny node limit still yields a move.
+        let mut best_move = moves[0];
+
+        for depth in 1..=Game::MAX_SEARCH_DEPTH {
+            let mut best_move_at_depth = moves[0];
+            let mut best_score_at_depth = i32::MIN;
+
+            for (from, to) in moves.iter() {
+                let game_after_movement = self.clone_with_move(*from, *to);
+                let score = match game_after_movement.negamax(depth - 1, 1, -i32::MAX, i32::MAX, &mut nodes, max_nodes) {
+                    Some(score) => -score,
+                    None => return (Some(best_move), nodes), // We have run out of nodes
+                };
+
+                if score > best_score_at_depth {
+                    best_score_at_depth = score;
+                    best_move_at_depth = (*from, *to);
+                }
+            }
+
+            best_move = best_move_at_depth;
+
+            // If we have found a forced mate (in either direction), searching deeper will not change the outcome.
+            if best_score_at_depth.abs() >= Game::MATE_SCORE - Game::MAX_SEARCH_DEPTH as i32 {
+                break;
+            }
+        }
+
+        (Some(best_move), nodes)
+    }
+
+    /// Returns every legal move of the active colour paired with its evaluation by a search `depth` plies deep, sorted best-first for the active colour.
+    /// Scores are given in pawns from the active colour's perspective, with forced mates scored close to `MATE_SCORE`.
+    ///
+    /// A depth of 0 is treated as a depth of 1, since each move has to be made to be evaluated. Moves with equal scores keep the order
+    /// in which they are generated, such that the first move is the one `best_move_with_limit` would choose at the same depth.
+    pub fn evaluate_all_moves(&self, depth: u32) -> Vec<((Position, Position), i32)> {
+        let mut nodes: u64 = 0;
+        let mut evaluated_moves: Vec<((Position, Position), i32)> = Vec::new();
+
+        for (from, to) in self.all_legal_moves() {
+            // Unwrapping is safe here since the search has no node limit.
+            let score = -self
+                .clone_with_move(from, to)
+                .negamax(depth.max(1) - 1, 1, -i32::MAX, i32::MAX, &mut nodes, u64::MAX)
+                .unwrap();
+            evaluated_moves.push(((from, to), score));
+        }
+
+        // sort_by_key is stable, so equally scored moves keep their order
+        evaluated_moves.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        evaluated_moves
+    }
+
+    /// Searches `depth` plies deep for the best move of the active colour using negamax with alpha-beta pruning, and returns it as a (from, to)-pair.
+    /// Positions are scored by material, and checkmates are scored such that shorter mates are preferred.
+    ///
+    /// A depth of 0 is treated as a depth of 1. Returns `None` if the game has ended or the active colour has no legal moves.
+    pub fn search_best_move(&self, depth: u32) -> Option<(Position, Position)> {
+        if self.state != GameState::InProgress && self.state != GameState::Check {
+            return None;
+        }
+
+        let mut nodes: u64 = 0;
+        let mut best_move: Option<(Position, Position)> = None;
+        let mut alpha = -i32::MAX;
+
+        for (from, to) in self.all_legal_moves() {
+            // Unwrapping is safe here since the search has no node limit.
+            let score = -self
+                .clone_with_move(from, to)
+                .negamax(depth.max(1) - 1, 1, -i32::MAX, -alpha, &mut nodes, u64::MAX)
+                .unwrap();
+
+            if best_move.is_none() || score > alpha {
+                alpha = score;
+                best_move = Some((from, to));
+            }
+        }
+
+        best_move
+    }
+
+    /// Counts every position (internal nodes and leaves, including the current position) reachable within `depth` plies.
+    /// Useful for benchmarking the overhead of a search separately from counting leaves.
+    ///
+    /// Pawns reaching the last row are counted once, as promotions to queens.
+    pub fn node_count(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.all_legal_moves();
+        if depth == 1 {
+            // The positions after the moves are leaves, so we do not need to perform the moves to count them
+            return 1 + moves.len() as u64;
+        }
+
+        let mut nodes = 1;
+        for (from, to) in moves {
+            nodes += self.clone_with_move(from, to).node_count(depth - 1);
+        }
+        nodes
+    }
+
+    /// Counts the leaf positions reachable in exactly `depth` plies, the standard benchmark for validating move generation.
+    /// The counts can be compared against published perft values, e.g. 20, 400 and 8902 from the starting position.
+    ///
+    /// Pawns reaching the last row are counted once, as promotions to queens, whereas published values count all four promotions.
+    /// Castling and en passant are not implemented, so perft reveals their absence by falling short of published values
+    /// in positions where they are possible, e.g. from depth 5 on from the starting position.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.all_legal_moves();
+        if depth == 1 {
+            // Every move leads to a leaf, so we do not need to perform the moves to count them
+            return moves.len() as u64;
+        }
+
+        let mut leaves = 0;
+        for (from, to) in moves {
+            leaves += self.clone_with_move(from, to).perft(depth - 1);
+        }
+        leaves
+    }
+
+    /// Evaluates the game with a negamax search `depth` plies deep and returns the score from the active colour's perspective.
+    /// `ply` is the distance from the root of the search and is used to prefer shorter mates.
+    ///
+    /// Moves are pruned once they are known not to affect the result, i.e. once the score reaches `beta`, in which case the returned score
+    /// is only a lower bound. Scores at or below `alpha` are likewise only upper bounds. Pass `-i32::MAX` and `i32::MAX` for an exact score.
+    ///
+    /// Every visited position is counted in `nodes`. Returns `None` if `max_nodes` positions have already been visited.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn negamax(&self, depth: u32, ply: u32, mut alpha: i32, beta: i32, nodes: &mut u64, max_nodes: u64) -> Option<i32> {
+        if *nodes >= max_nodes {
+            return None;
+        }
+        *nodes += 1;
+
+        if self.state == GameState::GameOver {
+            return Some(self.terminal_score(ply));
+        }
+        if self.state == GameState::Draw {
+            return Some(0);
+        }
+        if depth == 0 {
+            return Some(self.evaluate());
+        }
+
+        let moves = self.all_legal_moves();
+        if moves.is_empty() {
+            return Some(self.terminal_score(ply));
+        }
+
+        let mut best_score = -i32::MAX;
+        for (from, to) in moves {
+            let score = -self
+                .clone_with_move(from, to)
+                .negamax(depth - 1, ply + 1, -beta, -alpha, nodes, max_nodes)?;
+            if score > best_score {
+                best_score = score;
+            }
+            if score > alpha {
+                alpha = score;
+            }
+            if alpha >= beta {
+                // The opponent will never allow this position, so the remaining moves do not matter
+                break;
+            }
+        }
+
+        Some(best_score)
+    }
+
+    /// Returns the score of a position where the active colour cannot move: a loss if it is checkmated and 0 if it is a stalemate.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn terminal_score(&self, ply: u32) -> i32 {
+        if self.is_in_check(self.active_colour) {
+            -Game::MATE_SCORE + ply as i32
+        } else {
+            0
+        }
+    }
+
+    /// Returns the total value of the pieces of colour `colour` in pawns, using the values from `PieceType::value`.
+    /// The starting position has a material count of 39 per colour.
+    pub fn material_count(&self, colour: Colour) -> u32 {
+        let mut count = 0;
+        for piece in self.board.iter().flatten() {
+            if piece.colour == colour {
+                // Piece values are never negative, so the cast is safe.
+                count += piece.piece_type.value() as u32;
+            }
+        }
+        count
+    }
+
+    /// Returns the number of legal moves of all pieces of colour `colour` combined, e.g. to measure how mobile the colour is in an evaluation.
+    /// The moves are counted as if it was the turn of `colour`, so the mobility of the inactive colour is counted too.
+    pub fn mobility(&self, colour: Colour) -> usize {
+        let mut mobility = 0;
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing
+            } else if piece.unwrap().colour == colour {
+                // Unwrapping piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                mobility += self.possible_moves(Position::new_from_idx(i).unwrap()).len();
+            }
+        }
+
+        mobility
+    }
+
+    /// Returns the material of White minus the material of Black in pawns, see `material_count`.
+    /// A positive balance means that White is ahead in material, and a negative balance that Black is.
+    pub fn material_balance(&self) -> i32 {
+        self.material_count(Colour::White) as i32 - self.material_count(Colour::Black) as i32
+    }
+
+    /// Returns the material of the active colour minus the material of the opponent, see `material_balance`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn evaluate(&self) -> i32 {
+        match self.active_colour {
+            Colour::White => self.material_balance(),
+            Colour::Black => -self.material_balance(),
+        }
+    }
+
+    /// Returns every legal move of the active colour as a list of (from, to)-pairs, e.g. for AIs and move counting.
+    ///
+    /// The moves are generated from the position only, so a game that has ended by a draw rule or a resignation still has moves listed.
+    pub fn all_legal_moves(&self) -> Vec<(Position, Position)> {
+        let mut moves: Vec<(Position, Position)> = Vec::new();
+
+        for (i, piece) in self.board.iter().enumerate() {
+            if piece.is_none() {
+                // Do nothing
+            } else if piece.unwrap().colour == self.active_colour {
+                // Unwrapping piece is safe here since it is not none.
+                // Unwrapping Position::new_from_idx(i) is safe here since the board is well defined.
+                let from = Position::new_from_idx(i).unwrap();
+                for to in self.possible_moves(from) {
+                    moves.push((from, to));
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Returns a legal move of the active colour picked uniformly at random using `rng`, e.g. for a trivial opponent,
+    /// or `None` if the active colour has no legal moves, i.e. in checkmate or stalemate. See `all_legal_moves`.
+    ///
+    /// Only available with the `rand` feature.
+    #[cfg(feature = "rand")]
+    pub fn random_move(&self, rng: &mut impl rand::Rng) -> Option<(Position, Position)> {
+        let moves = self.all_legal_moves();
+        if moves.is_empty() {
+            return None;
+        }
+        Some(moves[rng.gen_range(0..moves.len())])
+    }
+
+    /// Returns a clone of the game where the legal move from `from` to `to` has been made. Pawns reaching the last row are promoted to queens.
+    ///
+    /// The move is made even if the game has ended by a draw rule, an agreement, a resignation or a timeout, since `all_legal_moves`
+    /// lists the moves of the position regardless of how the game ended. The state of the clone is computed anew after the move.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS. Panics if the move is not legal.
+    fn clone_with_move(&self, from: Position, to: Position) -> Game {
+        let mut game_after_movement = self.clone();
+        // The move is only tried out, so it should not press the clock
+        game_after_movement.clock = None;
+        // nor should it be stopped by how the game ended, see all_legal_moves
+        if game_after_movement.state == GameState::GameOver || game_after_movement.state == GameState::Draw {
+            game_after_movement.state = GameState::InProgress;
+        }
+        game_after_movement
+            .make_move_pos(from, to)
+            .expect("clone_with_move was called with an illegal move!");
+
+        if game_after_movement.state == GameState::WaitingOnPromotionChoice {
+            // unwrap is safe since the game is waiting on a promotion
+            game_after_movement.set_promotion_piece(PieceType::Queen).unwrap();
+        }
+
+        game_after_movement
+    }
+}
+
+/// Here we implement the rendering functions of our game.
+impl Game {
+    /// Renders the board the same way as the `Display` implementation, but with `empty_token` printed on empty squares instead of `*`.
+    /// The token is padded to the width of a square, so it should be at most three characters long to keep the columns aligned.
+    ///
+    /// E.g. `game.to_string_with_empty(".")` renders empty squares as ` .  `.
+    pub fn to_string_with_empty(&self, empty_token: &str) -> String {
+        self.render_board(empty_token, Colour::White)
+    }
+
+    /// Renders the board the same way as the `Display` implementation, but from the perspective of `perspective`.
+    /// For `Colour::White` the output is identical to `Display`, which prints row 1 at the top.
+    /// For `Colour::Black` the rows keep that order, such that the black pieces stay at the bottom, and the order of the columns is reversed,
+    /// such that the board is seen from Black's side with a8 in the bottom right corner and h1 in the top left corner.
+    pub fn to_string_oriented(&self, perspective: Colour) -> String {
+        self.render_board("*", perspective)
+    }
+
+    /// Renders the board the same way as the `Display` implementation, but with each row prefixed by its number 1-8
+    /// and with a line of the column letters a-h below the board, aligned with the squares.
+    pub fn to_labeled_string(&self) -> String {
+        let mut output = String::new();
+
+        for (i, line) in self.to_string_with_empty("*").lines().enumerate() {
+            // the first and last lines are the top and bottom of the frame, the lines in between are the rows 1-8
+            if i == 0 || i == 9 {
+                output.push_str("  ");
+            } else {
+                output.push_str(&format!("{} ", i));
+            }
+            output.push_str(line);
+            output.push('\n');
+        }
+
+        // each square is four characters wide, and its content starts after the row label, the frame and a space
+        output.push_str("    a   b   c   d   e   f   g   h");
+
+        output
+    }
+
+    /// Renders the board with `empty_token` on empty squares, from the perspective of `perspective`. See `to_string_with_empty` and `to_string_oriented`.
+    ///
+    /// SHOULD ONLY BE CALLED BY INTERNAL FUNCTIONS.
+    fn render_board(&self, empty_token: &str, perspective: Colour) -> String {
+        // init output, the string we'll be coding our format to
+        let mut output = String::new();
+
+        // start with the top row
+        output.push_str("|:------------------------------:|\n");
+
+        // for every Option<piece> in board, print a representation. Also, for every beginning of a row i % 8 == 0 and end of a row i & 8 == 7 add corresponding slices.
+        // From Black's perspective, the order of the columns within every row is reversed.
+        for i in 0..64 {
+            let piece = match perspective {
+                Colour::White => &self.board[i],
+                Colour::Black => &self.board[i - i % 8 + 7 - i % 8],
+            };
+
+            if i % 8 == 0 {
+                output.push_str("|");
+            }
+
+            if piece.is_none() {
+                output.push_str(&format!(" {:<3}", empty_token)); // there is no piece here, add the empty token
+            } else {
+                // from here, unwrapping is safe since the piece is not None
+                // add initial spacing
+                output.push_str(" ");
+
+                // match dict for Colour representation
+                output.push_str(match piece.unwrap().colour {
+                    Colour::White => "w",
+                    Colour::Black => "b",
+                });
+
+                // match dict for PieceType representation
+                output.push_str(match piece.unwrap().piece_type {
+                    PieceType::King => "K ",
+                    PieceType::Queen => "Q ",
+                    PieceType::Bishop => "B ",
+                    PieceType::Knight => "Kn",
+                    PieceType::Rook => "R ",
+                    PieceType::Pawn => "P ",
+                });
+            }
+
+            if i % 8 == 7 {
+                output.push_str("|\n");
+            }
+        }
+
+        // end with the bottom row
+        output.push_str("|:------------------------------:|");
+
+        output
+    }
+
+    /// Renders the board with one Unicode chess glyph per occupied square and a dot on empty squares, with the squares separated by spaces.
+    /// The rows are rendered in the same order as the `Display` implementation, starting with the first row.
+    ///
+    /// E.g. the first row of a new game is rendered as `♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖`.
+    pub fn to_unicode_string(&self) -> String {
+        let mut output = String::new();
+
+        for (i, piece) in self.board.iter().enumerate() {
+            match piece {
+                None => output.push('·'),
+                Some(piece) => output.push(piece.to_unicode_char()),
+            }
+
+            if i % 8 == 7 {
+                if i != 63 {
+                    output.push('\n');
+                }
+            } else {
+                output.push(' ');
+            }
+        }
+
+        output
+    }
+}
+
+/// Here we implement the notation functions of our game.
+impl Game {
+    /// Initialises a game from a position in Forsyth–Edwards Notation (FEN), e.g. for loading puzzles and test positions.
+    /// The state of the game is computed from the position.
+    ///
+    /// Castling and en passant are not implemented, so those fields are validated but otherwise ignored.
+    /// The halfmove clock and fullmove number are read from the move counter fields.
+    ///
+    /// Returns an `Ok(Game)`,
+    /// or an `Err(ChessError)` describing the error if the FEN is malformed or does not contain exactly one king per colour.
+    pub fn from_fen(fen: &str) -> Result<Game, ChessError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 6 {
+            return Err(ChessError::ParseError(format!(
+                "FEN should contain 6 fields separated by spaces, but {} were found.",
+                fields.len()
+            )));
+        }
+
+        // Piece placement, from row 8 to row 1 and from column a to column h within each row
+        let rows: Vec<&str> = fields[0].split('/').collect();
+        if rows.len() != 8 {
+            return Err(ChessError::ParseError(format!(
+                "FEN piece placement should contain 8 rows separated by '/', but {} were found.",
+                rows.len()
+            )));
+        }
+
+        let mut board: [Option<Piece>; 8 * 8] = [None; 8 * 8];
+        let mut white_kings = 0;
+        let mut black_kings = 0;
+        for (i, row_str) in rows.iter().enumerate() {
+            let row = 7 - i;
+            let mut col = 0;
+            for c in row_str.chars() {
+                if let Some(empty_squares) = c.to_digit(10) {
+                    if empty_squares == 0 || empty_squares > 8 {
+                        return Err(ChessError::ParseError(format!("Invalid number of empty squares '{}' in FEN row {}.", c, row + 1)));
+                    }
+                    col += empty_squares as usize;
+                } else {
+                    let piece = match Piece::from_fen_char(c) {
+                        None => return Err(ChessError::ParseError(format!("Invalid character '{}' in FEN row {}.", c, row + 1))),
+                        Some(piece) => piece,
+                    };
+                    if col >= 8 {
+                        return Err(ChessError::ParseError(format!("FEN row {} describes more than 8 squares.", row + 1)));
+                    }
+                    if piece.piece_type == PieceType::Pawn && (row == 0 || row == 7) {
+                        return Err(ChessError::ParseError(format!("FEN row {} contains a pawn, which is not allowed on the first or last row.", row + 1)));
+                    }
+                    if piece.piece_type == PieceType::King {
+                        match piece.colour {
+                            Colour::White => white_kings += 1,
+                            Colour::Black => black_kings += 1,
+                        }
+                    }
+                    board[row * 8 + col] = Some(piece);
+                    col += 1;
+                }
+            }
+            if col != 8 {
+                return Err(ChessError::ParseError(format!(
+                    "FEN row {} should describe 8 squares, but it describes {}.",
+                    row + 1,
+                    col
+                )));
+            }
+        }
+
+        if white_kings != 1 || black_kings != 1 {
+            return Err(ChessError::ParseError(format!(
+                "There should be exactly one king per colour, but there are {} white and {} black kings.",
+                white_kings, black_kings
+            )));
+        }
+
+        // Active colour
+        let active_colour = match fields[1] {
+            "w" => Colour::White,
+            "b" => Colour::Black,
+            _ => return Err(ChessError::ParseError(format!("Invalid active colour '{}' in FEN, should be 'w' or 'b'.", fields[1]))),
+        };
+
+        // Castling availability
+        if fields[2] != "-" && !fields[2].chars().all(|c| "KQkq".contains(c)) {
+            return Err(ChessError::ParseError(format!("Invalid castling availability '{}' in FEN.", fields[2])));
+        }
+
+        // En passant target
+        if fields[3] != "-" && Position::parse_str(fields[3]).is_err() {
+            return Err(ChessError::ParseError(format!("Invalid en passant target '{}' in FEN.", fields[3])));
+        }
+
+        // Halfmove clock and fullmove number
+        let halfmove_clock = match fields[4].parse::<u32>() {
+            Ok(halfmove_clock) => halfmove_clock,
+            Err(_) => return Err(ChessError::ParseError(format!("Invalid halfmove clock '{}' in FEN.", fields[4]))),
+        };
+        let fullmove_number = match fields[5].parse::<u32>() {
+            Ok(fullmove_number) if fullmove_number > 0 => fullmove_number,
+            _ => return Err(ChessError::ParseError(format!("Invalid fullmove number '{}' in FEN.", fields[5]))),
+        };
+
+        let mut game = Game::new();
+        game.set_board(board);
+        game.active_colour = active_colour;
+        game.halfmove_clock = halfmove_clock;
+        game.fullmove_number = fullmove_number;
+        game.position_counts.clear();
+        game.record_position();
+        game.update_game_state();
+
+        Ok(game)
+    }
+
+    /// Returns the current position in Forsyth–Edwards Notation (FEN), e.g. for handing the position to external engines.
+    ///
+    /// Castling and en passant are not implemented, so their fields are always `-`.
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        // Piece placement, from row 8 to row 1 and from column a to column h within each row
+        for row in (0..8).rev() {
+            let mut empty_squares = 0;
+            for col in 0..8 {
+                match self.board[row * 8 + col] {
+                    None => empty_squares += 1,
+                    Some(piece) => {
+                        if empty_squares > 0 {
+                            fen.push_str(&empty_squares.to_string());
+                            empty_squares = 0;
+                        }
+                        fen.push(piece.to_fen_char());
+                    }
+                }
+            }
+            if empty_squares > 0 {
+                fen.push_str(&empty_squares.to_string());
+            }
+            if row > 0 {
+                fen.push('/');
+            }
+        }
+
+        // Active colour
+        fen.push_str(match self.active_colour {
+            Colour::White => " w",
+            Colour::Black => " b",
+        });
+
+        // Castling availability and en passant target
+        fen.push_str(" - -");
+
+        // Halfmove clock and fullmove number
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
+
+        fen
+    }
+
+    /// Initialises a game from a board drawn on the same format as the `Display` implementation, e.g. for writing test fixtures that read like a board.
+    /// The first grid row is row 1 and the last is row 8, squares are given as `wR`, `bKn`, etc., and empty squares as `*`.
+    /// Border lines such as `|:------------------------------:|` and blank lines are skipped.
+    ///
+    /// White is the active colour and the state of the game is computed from the position, like in `from_fen`.
+    ///
+    /// Returns an `Ok(Game)`,
+    /// or an `Err(ChessError)` describing the error if the board is malformed or does not contain exactly one king per colour.
+    pub fn from_ascii(ascii: &str) -> Result<Game, ChessError> {
+        let rows: Vec<&str> = ascii
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with("|:"))
+            .collect();
+        if rows.len() != 8 {
+            return Err(ChessError::ParseError(format!("The board should contain 8 rows, but {} were found.", rows.len())));
+        }
+
+        let mut board: [Option<Piece>; 8 * 8] = [None; 8 * 8];
+        let mut white_kings = 0;
+        let mut black_kings = 0;
+        for (row, row_str) in rows.iter().enumerate() {
+            let squares: Vec<&str> = row_str.trim_matches('|').split_whitespace().collect();
+            if squares.len() != 8 {
+                return Err(ChessError::ParseError(format!(
+                    "Row {} of the board should contain 8 squares, but {} were found.",
+                    row + 1,
+                    squares.len()
+                )));
+            }
+
+            for (col, square) in squares.iter().enumerate() {
+                if *square == "*" {
+                    continue;
+                }
+
+                // The first character is the colour and the rest is the piece type
+                let colour = match square.get(0..1) {
+                    Some("w") => Colour::White,
+                    Some("b") => Colour::Black,
+                    _ => return Err(ChessError::ParseError(format!("Invalid square '{}' in row {} of the board.", square, row + 1))),
+                };
+                let piece_type = match &square[1..] {
+                    "K" => PieceType::King,
+                    "Q" => PieceType::Queen,
+                    "R" => PieceType::Rook,
+                    "Kn" => PieceType::Knight,
+                    "B" => PieceType::Bishop,
+                    "P" => PieceType::Pawn,
+                    _ => return Err(ChessError::ParseError(format!("Invalid square '{}' in row {} of the board.", square, row + 1))),
+                };
+
+                if piece_type == PieceType::Pawn && (row == 0 || row == 7) {
+                    return Err(ChessError::ParseError(format!("Row {} of the board contains a pawn, which is not allowed on the first or last row.", row + 1)));
+                }
+                if piece_type == PieceType::King {
+                    match colour {
+                        Colour::White => white_kings += 1,
+                        Colour::Black => black_kings += 1,
+                    }
+                }
+                board[row * 8 + col] = Some(Piece { piece_type, colour });
+            }
+        }
+
+        if white_kings != 1 || black_kings != 1 {
+            return Err(ChessError::ParseError(format!(
+                "There should be exactly one king per colour, but there are {} white and {} black kings.",
+                white_kings, black_kings
+            )));
+        }
+
+        let mut game = Game::new();
+        game.set_board(board);
+        game.position_counts.clear();
+        game.record_position();
+        game.update_game_state();
+
+        Ok(game)
+    }
+
+    /// Returns the game in Portable Game Notation (PGN), e.g. for pasting the game into analysis tools.
+    ///
+    /// The PGN is minimal: it contains the `Result` tag, and the `SetUp` and `FEN` tags if the game did not start from the starting position,
+    /// followed by the moves in SAN and the result, which is `1-0`, `0-1`, `1/2-1/2`, or `*` if the game has not ended.
+    pub fn to_pgn(&self) -> String {
+        let result = match self.result {
+            None => "*",
+            Some(GameResult::Checkmate(Colour::White))
+            | Some(GameResult::Resignation(Colour::White))
+            | Some(GameResult::Timeout(Colour::White)) => "1-0",
+            Some(GameResult::Checkmate(Colour::Black))
+            | Some(GameResult::Resignation(Colour::Black))
+            | Some(GameResult::Timeout(Colour::Black)) => "0-1",
+            Some(_) => "1/2-1/2",
+        };
+
+        // Take back every move to find the position that the game started from
+        let mut game = self.clone();
+        game.clock = None;
+        while game.undo_move().is_ok() {}
+
+        let mut pgn = format!("[Result \"{}\"]\n", result);
+        let start_fen = game.to_fen();
+        if start_fen != Game::new().to_fen() {
+            pgn.push_str("[SetUp \"1\"]\n");
+            pgn.push_str(&format!("[FEN \"{}\"]\n", start_fen));
+        }
+        pgn.push('\n');
+
+        // Replay the moves, writing each in SAN before it is made
+        let mut movetext: Vec<String> = Vec::new();
+        for (i, entry) in self.history.iter().enumerate() {
+            let (from, to) = entry.mv;
+            if game.active_colour == Colour::White {
+                movetext.push(format!("{}.", game.fullmove_number));
+            } else if i == 0 {
+                movetext.push(format!("{}...", game.fullmove_number));
+            }
+
+            // Unwrapping is safe here since the moves in the history were legal when they were made.
+            movetext.push(game.move_to_san(from, to, entry.promotion).unwrap());
+            game.make_move_pos(from, to).unwrap();
+            if let Some(piece_type) = entry.promotion {
+                game.set_promotion_piece(piece_type).unwrap();
+            }
+        }
+        movetext.push(String::from(result));
+
+        pgn.push_str(&movetext.join(" "));
+        pgn.push('\n');
+        pgn
+    }
+}
+
+/// Here we implement the debugging functions of our game.
+impl Game {
+    /// Returns a dump of the game state, intended to be pasted into bug reports such that the issue can be reproduced.
+    ///
+    /// Contains the FEN, the state, the active colour, the last moved-to position, the moves made so far in UCI notation
+    /// (since the game started or its position was set up) and the board.
+    pub fn debug_dump(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("fen: {}\n", self.to_fen()));
+        output.push_str(&format!("state: {:?}\n", self.state));
+        output.push_str(&format!("active colour: {}\n", self.active_colour));
+        output.push_str(&format!("last moved to: {}\n", self.last_moved_to));
+        let moves: Vec<String> = self
+            .history
+            .iter()
+            .map(|entry| format!("{}{}", entry.mv.0, entry.mv.1))
+            .collect();
+        output.push_str(&format!("moves: {}\n", moves.join(" ")));
+        output.push_str(&format!("board:\n{}", self));
+
+        output
+    }
+}
+
+impl Default for Game {
+    // The default game is a new game from the starting position, see `Game::new`.
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+/// Implement print routine for Game.
+///
+/// Output example:
+/// |:------------------------------:|
+/// | wR  wKn wB  wQ  wK  wB  wKn wR |
+/// | wP  wP  wP  wP  wP  wP  wP  wP |
+/// | *   *   *   *   *   *   *   *  |
+/// | *   *   *   *   *   *   *   *  |
+/// | *   *   *   *   *   *   *   *  |
+/// | *   *   *   *   *   *   *   *  |
+/// | bP  bP  bP  bP  bP  bP  bP  bP |
+/// | bR  bKn bB  bQ  bK  bB  bKn bR |
+/// |:------------------------------:|
+///
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // empty squares are marked with an asterisk
+        write!(f, "{}", self.to_string_with_empty("*"))
+    }
+}
+
+impl fmt::Debug for Game {
+    // Make the formatter print game the same in debug mode as outside of debug mode.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for Position {
+    // Print positions on the same format XF as they are parsed by parse_str.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let col = (b'a' + self.col as u8) as char;
+        write!(f, "{}{}", col, self.row + 1)
+    }
+}
+
+impl fmt::Display for ChessError {
+    // Print errors as messages that can be shown to the user.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChessError::OffBoard => write!(f, "The position is not on the board."),
+            ChessError::NoPieceAtSource => write!(f, "There is no piece on the square you are trying to move from."),
+            ChessError::WrongColour => write!(f, "It is not this colour's turn!"),
+            ChessError::IllegalMove => write!(
+                f,
+                "Illegal move. (This might mean that this piece cannot move this way, or that it puts your king in check!)"
+            ),
+            ChessError::NotPlayable(state) => write!(f, "The game is not in a state where this can be done. Currently, the state is {:?}.", state),
+            ChessError::InvalidPromotion(message) => write!(f, "{}", message),
+            ChessError::NothingToUndo => write!(f, "There is no move to undo."),
+            ChessError::NoDrawOffer => write!(f, "There is no draw offer to accept."),
+            ChessError::DrawNotClaimable => write!(f, "A draw cannot be claimed by this rule in the current position."),
+            ChessError::ParseError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ChessError {}
+
+impl fmt::Display for Colour {
+    // Make the formatter print colours fancily outside of debug mode.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Colour {
+    type Err = ChessError;
+
+    /// Parses a colour from its name or its first letter, e.g. `white` or `w`. Performs trimming and caps-handling.
+    fn from_str(s: &str) -> Result<Colour, ChessError> {
+        match s.trim().to_lowercase().as_str() {
+            "white" | "w" => Ok(Colour::White),
+            "black" | "b" => Ok(Colour::Black),
+            _ => Err(ChessError::ParseError(format!("Invalid colour '{}'.", s.trim()))),
+        }
+    }
+}
+
+impl fmt::Display for PieceType {
+    // Make the formatter print piece types fancily outside of debug mode.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for PieceType {
+    type Err = ChessError;
+
+    /// Parses a piece type from its name or its letter in algebraic notation, e.g. `queen` or `q`. Performs trimming and caps-handling.
+    fn from_str(s: &str) -> Result<PieceType, ChessError> {
+        match s.trim().to_lowercase().as_str() {
+            "king" | "k" => Ok(PieceType::King),
+            "queen" | "q" => Ok(PieceType::Queen),
+            "rook" | "r" => Ok(PieceType::Rook),
+            "bishop" | "b" => Ok(PieceType::Bishop),
+            "knight" | "n" => Ok(PieceType::Knight),
+            "pawn" | "p" => Ok(PieceType::Pawn),
+            _ => Err(ChessError::ParseError(format!("Invalid input '{}'.", s.trim().to_lowercase()))),
+        }
+    }
+}
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::Bitboards;
+    use super::ChessError;
+    use super::Colour;
+    use super::DrawRule;
+    use super::Duration;
+    use super::Game;
+    use super::GameClock;
+    use super::GameResult;
+    use super::GameState;
+    use super::Move;
+    use super::Piece;
+    use super::PieceType;
+    use super::Position;
+
+    // helper that builds a game with only the given pieces on the board, the positions given on the format XF
+    fn game_with_pieces(pieces: &[(&str, PieceType, Colour)]) -> Game {
+        let mut game = Game::new();
+        game.set_board([None; 8 * 8]);
+
+        for (pos, piece_type, colour) in pieces {
+            let piece = Some(Piece {
+                piece_type: *piece_type,
+                colour: *colour,
+            });
+            game.set_square(Position::parse_str(pos).unwrap().idx, piece);
+        }
+
+        game
+    }
+
+    // check test framework
+    #[test]
+    fn it_works() {
+        assert_eq!(2 + 2, 4);
+    }
+
+    // example test
+    // check that game state is in progress after initialisation
+    #[test]
+    fn game_in_progress_after_init() {
+        let game = Game::new();
+
+        println!("{}", game);
+
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+    }
+
+    // verify that pieces and positions can be used as keys in hashed collections
+    #[test]
+    fn pieces_in_hash_set() {
+        let game = Game::new();
+        let mut pieces: std::collections::HashSet<Piece> = game.get_board().iter().flatten().copied().collect();
+        // six piece types of two colours
+        assert_eq!(pieces.len(), 12);
+        assert!(pieces.contains(&Piece::new(PieceType::Knight, Colour::Black)));
+        assert!(!pieces.insert(Piece::new(PieceType::King, Colour::White)));
+
+        let positions: std::collections::HashSet<Position> = game.find_pieces(PieceType::Pawn, Colour::White).into_iter().collect();
+        assert_eq!(positions.len(), 8);
+        assert!(positions.contains(&Position::parse_str("e2").unwrap()));
+    }
+
+    // verify that mobility counts the legal moves of every piece of a colour
+    #[test]
+    fn mobility_from_start() {
+        let mut game = Game::new();
+        assert_eq!(game.mobility(Colour::White), 20);
+        assert_eq!(game.mobility(Colour::Black), 20);
+
+        // 1.e4 opens up the bishop, the queen and the king, while the pawn has one move less
+        game.make_move("e2", "e4").unwrap();
+        assert_eq!(game.mobility(Colour::White), 30);
+        assert_eq!(game.mobility(Colour::Black), 20);
+    }
+
+    // verify that the default game is a new game
+    #[test]
+    fn default_game_is_new_game() {
+        let game = Game::default();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.get_active_colour(), Colour::White);
+        assert_eq!(game.to_fen(), Game::new().to_fen());
+    }
+
+    // verify that errors can be told apart by their variant rather than by their message
+    #[test]
+    fn errors_are_matchable() {
+        assert_eq!(Position::new(8, 0), Err(ChessError::OffBoard));
+        assert_eq!(Position::new_from_idx(64), Err(ChessError::OffBoard));
+        assert_eq!(Position::new(0, 7).unwrap().offset((0, 1)), Err(ChessError::OffBoard));
+        assert!(matches!(Position::parse_str("i9"), Err(ChessError::ParseError(_))));
+
+        let mut game = Game::new();
+        assert_eq!(game.make_move("e2", "e5"), Err(ChessError::IllegalMove));
+        assert_eq!(game.make_move("e3", "e4"), Err(ChessError::NoPieceAtSource));
+        assert_eq!(game.make_move("e7", "e5"), Err(ChessError::WrongColour));
+        assert!(matches!(game.make_move("e2", "e9"), Err(ChessError::ParseError(_))));
+        assert_eq!(game.undo_move(), Err(ChessError::NothingToUndo));
+        assert_eq!(game.accept_draw(), Err(ChessError::NoDrawOffer));
+
+        assert_eq!(
+            game.set_promotion_piece(PieceType::Queen),
+            Err(ChessError::NotPlayable(GameState::InProgress))
+        );
+        assert_eq!(
+            ChessError::IllegalMove.to_string(),
+            "Illegal move. (This might mean that this piece cannot move this way, or that it puts your king in check!)"
+        );
+    }
+
+    // verify that whitespace and uppercase characters are tolerated when parsing positions and moves
+    #[test]
+    fn parse_str_tolerates_whitespace() {
+        let e2 = Position::new(1, 4).unwrap();
+        assert_eq!(Position::parse_str("  e2 "), Ok(e2));
+        assert_eq!(Position::parse_str("E2"), Ok(e2));
+        assert_eq!(Position::parse_str("\te 2\n"), Ok(e2));
+        for invalid in ["", "   ", "e", "e2 e4", "e22"] {
+            assert!(Position::parse_str(invalid).is_err(), "{:?} should not parse", invalid);
+        }
+
+        // runs of whitespace between the positions of a move are ignored too
+        let input: Vec<&str> = "e2  e4".split_whitespace().collect();
+        assert_eq!(input, vec!["e2", "e4"]);
+        let mut game = Game::new();
+        assert_eq!(game.make_move_str("e2  e4"), Ok(GameState::InProgress));
+        assert_eq!(game.make_move(" e7 ", "  E5"), Ok(GameState::InProgress));
+    }
+
+    #[test]
+    fn position_checking_works() {
+        let possible_moves = vec![Position::new(0, 0).unwrap()];
+        let other_position = Position::new(0, 0).unwrap();
+        assert!(possible_moves
+            .iter() // Creates an iterable of positions.
+            .any(|pos| pos == &other_position)); // Checks if our position is equal to the list of possible moves. We use .any() since the objects may be different instances.
+    }
+
+    // check that game state is check after the queen attacks the king
+    #[test]
+    fn game_enters_check() {
+        let game = Game::new_from_moves(&[
+            ("e2", "e3"),
+            ("e7", "e6"),
+            ("d1", "g4"),
+            ("e6", "e5"),
+            ("g4", "e6"),
+        ])
+        .unwrap();
+
+        assert_eq!(game.get_game_state(), GameState::Check);
+    }
+
+    // check that the game state is checkmate after "skolmatt"
+    // due to the nature of the library, this also verifies that stalemate-checking will work
+    #[test]
+    fn game_enters_checkmate() {
+        let game = Game::new_from_moves(&[
+            ("e2", "e3"),
+            ("e7", "e6"),
+            ("d1", "f3"),
+            ("e6", "e5"),
+            ("f1", "c4"),
+            ("e5", "e4"),
+            ("f3", "f7"),
+        ])
+        .unwrap();
+
+        eprintln!("{}", game);
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+        assert!(game.is_in_check_now());
+        assert!(game.is_checkmate());
+        assert!(!game.is_stalemate());
+    }
+
+    // verify that the game enters the state waitingonpromotionchoice if a pawn should be promoted
+    #[test]
+    fn game_enters_waitingonpromitionchoice() {
+        let mut game = Game::new();
+        let moves: Vec<&str> = "d2 d3
+        e7 e6
+        d3 d4
+        e6 e5
+        d4 e5
+        e8 e7
+        e5 e6
+        e7 f6
+        e6 e7
+        f6 f5
+        e7 e8"
+            .split_whitespace()
+            .collect();
+
+        for i in 0..(moves.len() / 2) {
+            let result = game.make_move(moves[2 * i], moves[2 * i + 1]);
+            eprintln!(
+                "{} {}: {:?}",
+                moves[2 * i],
+                moves[2 * i + 1],
+                result.unwrap()
+            );
+        }
+
+        assert_eq!(game.get_game_state(), GameState::WaitingOnPromotionChoice);
+    }
+
+    // verify that a pawn can be promoted
+    #[test]
+    fn game_promotes_correctly() {
+        let mut game = Game::new();
+        let moves: Vec<&str> = "d2 d3
+        e7 e6
+        d3 d4
+        e6 e5
+        d4 e5
+        e8 e7
+        e5 e6
+        e7 f6
+        e6 e7
+        f6 f5
+        e7 e8"
+            .split_whitespace()
+            .collect();
+
+        for i in 0..(moves.len() / 2) {
+            let result = game.make_move(moves[2 * i], moves[2 * i + 1]);
+            eprintln!(
+                "{} {}: {:?}",
+                moves[2 * i],
+                moves[2 * i + 1],
+                result.unwrap()
+            );
+        }
+
+        assert_eq!(game.get_game_state(), GameState::WaitingOnPromotionChoice);
+        assert!(game.set_promotion(String::from("queen")).is_ok());
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        eprintln!("{}", game);
+    }
+
+    // verify that the output is accurate
+    #[test]
+    fn output_accurate() {
+        let game = Game::new();
+
+        assert_eq!(
+            format!("{}", game),
+            "|:------------------------------:|
+| wR  wKn wB  wQ  wK  wB  wKn wR |
+| wP  wP  wP  wP  wP  wP  wP  wP |
+| *   *   *   *   *   *   *   *  |
+| *   *   *   *   *   *   *   *  |
+| *   *   *   *   *   *   *   *  |
+| *   *   *   *   *   *   *   *  |
+| bP  bP  bP  bP  bP  bP  bP  bP |
+| bR  bKn bB  bQ  bK  bB  bKn bR |
+|:------------------------------:|"
+        );
+    }
+
+    // verify that all pieces are counted at the start of the game
+    #[test]
+    fn piece_count_at_start() {
+        let game = Game::new();
+
+        assert_eq!(game.piece_count(), 32);
+        assert_eq!(game.piece_count_by_colour(Colour::White), 16);
+        assert_eq!(game.piece_count_by_colour(Colour::Black), 16);
+    }
+
+    // verify that the empty token can be customised in the output
+    #[test]
+    fn output_with_custom_empty_token() {
+        let game = Game::new();
+
+        assert_eq!(
+            game.to_string_with_empty("."),
+            "|:------------------------------:|
+| wR  wKn wB  wQ  wK  wB  wKn wR |
+| wP  wP  wP  wP  wP  wP  wP  wP |
+| .   .   .   .   .   .   .   .  |
+| .   .   .   .   .   .   .   .  |
+| .   .   .   .   .   .   .   .  |
+| .   .   .   .   .   .   .   .  |
+| bP  bP  bP  bP  bP  bP  bP  bP |
+| bR  bKn bB  bQ  bK  bB  bKn bR |
+|:------------------------------:|"
+        );
+        assert_eq!(game.to_string_with_empty("*"), format!("{}", game));
+    }
+
+    // verify that the bishop pair requires bishops on squares of opposite colours
+    #[test]
+    fn bishop_pair_detected() {
+        let game = Game::new();
+        assert!(game.has_bishop_pair(Colour::White));
+        assert!(game.has_bishop_pair(Colour::Black));
+
+        // c1 is a dark square and f1 a light square
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("c1", PieceType::Bishop, Colour::White),
+            ("f1", PieceType::Bishop, Colour::White),
+        ]);
+        assert!(game.has_bishop_pair(Colour::White));
+        assert!(!game.has_bishop_pair(Colour::Black));
+
+        // c1 and e3 are both dark squares
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("c1", PieceType::Bishop, Colour::White),
+            ("e3", PieceType::Bishop, Colour::White),
+        ]);
+        assert!(!game.has_bishop_pair(Colour::White));
+    }
+
+    // verify that a selected piece can be moved to a selected square
+    #[test]
+    fn move_selected_moves_piece() {
+        let mut game = Game::new();
+        let from = Position::parse_str("e2").unwrap();
+        let to = Position::parse_str("e4").unwrap();
+
+        assert_eq!(game.move_selected(from, to, None), Ok(GameState::InProgress));
+        assert!(game.get_board()[from.idx].is_none());
+        assert_eq!(game.get_board()[to.idx].unwrap().piece_type, PieceType::Pawn);
+        assert_eq!(game.get_active_colour(), Colour::Black);
+
+        // illegal moves are rejected
+        let from = Position::parse_str("e7").unwrap();
+        let to = Position::parse_str("e4").unwrap();
+        assert!(game.move_selected(from, to, None).is_err());
+    }
+
+    // verify that a promoting move requires a promotion choice, and that the game is untouched if it is missing
+    #[test]
+    fn move_selected_requires_promotion() {
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e6", PieceType::King, Colour::Black),
+            ("a7", PieceType::Pawn, Colour::White),
+        ]);
+        let from = Position::parse_str("a7").unwrap();
+        let to = Position::parse_str("a8").unwrap();
+
+        assert!(game.move_selected(from, to, None).is_err());
+        assert_eq!(game.get_board()[from.idx].unwrap().piece_type, PieceType::Pawn);
+        assert!(game.get_board()[to.idx].is_none());
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        // a promotion cannot be supplied for a move that does not promote
+        let mut game = Game::new();
+        let from = Position::parse_str("e2").unwrap();
+        let to = Position::parse_str("e4").unwrap();
+        assert!(game.move_selected(from, to, Some(PieceType::Queen)).is_err());
+        assert!(game.get_board()[to.idx].is_none());
+    }
+
+    // verify that a supplied promotion is performed in the same call as the move
+    #[test]
+    fn move_selected_promotes() {
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e6", PieceType::King, Colour::Black),
+            ("a7", PieceType::Pawn, Colour::White),
+        ]);
+        let from = Position::parse_str("a7").unwrap();
+        let to = Position::parse_str("a8").unwrap();
+
+        assert!(game.move_selected(from, to, Some(PieceType::King)).is_err());
+        assert_eq!(
+            game.move_selected(from, to, Some(PieceType::Queen)),
+            Ok(GameState::InProgress)
+        );
+        assert_eq!(
+            game.get_board()[to.idx],
+            Some(Piece {
+                piece_type: PieceType::Queen,
+                colour: Colour::White
+            })
+        );
+    }
+
+    // verify that well-known openings are recognised
+    #[test]
+    fn eco_code_recognises_openings() {
+        let mut game = Game::new();
+        assert_eq!(game.eco_code(), None);
+
+        game.make_move("e2", "e4").unwrap();
+        assert_eq!(game.eco_code(), Some("B00"));
+
+        game.make_move("c7", "c5").unwrap();
+        assert_eq!(game.eco_code(), Some("B20"));
+
+        game.make_move("a2", "a3").unwrap();
+        assert_eq!(game.eco_code(), None);
+    }
+
+    // verify that square input is normalized to the format XF
+    #[test]
+    fn normalize_square_works() {
+        assert_eq!(Position::normalize_square(" E4 "), Ok(String::from("e4")));
+        assert_eq!(Position::normalize_square("h8"), Ok(String::from("h8")));
+        assert!(Position::normalize_square("z9").is_err());
+        assert!(Position::normalize_square("e44").is_err());
+    }
+
+    // verify that a side with insufficient mating material cannot win
+    #[test]
+    fn can_win_requires_mating_material() {
+        let game = Game::new();
+        assert!(game.can_win(Colour::White));
+        assert!(game.can_win(Colour::Black));
+
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("b1", PieceType::Knight, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("a7", PieceType::Pawn, Colour::Black),
+        ]);
+        assert!(!game.can_win(Colour::White));
+        assert!(game.can_win(Colour::Black));
+
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("c8", PieceType::Bishop, Colour::Black),
+        ]);
+        assert!(!game.can_win(Colour::White));
+        assert!(!game.can_win(Colour::Black));
+    }
+
+    // verify that a hypothetical piece gets its moves without the board being modified
+    #[test]
+    fn hypothetical_queen_reach() {
+        let game = Game::new();
+        let queen = Piece {
+            piece_type: PieceType::Queen,
+            colour: Colour::White,
+        };
+
+        let moves = game.hypothetical_moves(queen, Position::parse_str("d4").unwrap());
+        assert_eq!(moves.len(), 19);
+        for pos in ["d7", "a7", "g7", "a4", "h4", "c3", "e3", "d3"] {
+            assert!(moves.contains(&Position::parse_str(pos).unwrap()));
+        }
+        for pos in ["d2", "d8", "b2", "f2"] {
+            assert!(!moves.contains(&Position::parse_str(pos).unwrap()));
+        }
+        assert!(game.get_board()[Position::parse_str("d4").unwrap().idx].is_none());
+
+        // occupied squares cannot hold a hypothetical piece
+        assert!(game
+            .hypothetical_moves(queen, Position::parse_str("d2").unwrap())
+            .is_empty());
+    }
+
+    // verify that a hypothetical queen on an empty square gets the squares along its rays
+    #[test]
+    fn hypothetical_moves_on_empty_square() {
+        let game = game_with_pieces(&[
+            ("a1", PieceType::King, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+            ("e7", PieceType::Pawn, Colour::Black),
+        ]);
+        let e4 = Position::parse_str("e4").unwrap();
+        assert!(game.get_possible_moves(e4, 0).is_empty());
+
+        let moves = game.hypothetical_moves(Piece::new(PieceType::Queen, Colour::White), e4);
+        // 6 along the file up to the pawn, 7 along the row and 7 + 6 along the diagonals
+        assert_eq!(moves.len(), 26);
+        for pos in ["e1", "e7", "a4", "h4", "a8", "h1", "b1", "h7"] {
+            assert!(moves.contains(&Position::parse_str(pos).unwrap()));
+        }
+        for pos in ["e4", "e8", "a1", "h8"] {
+            assert!(!moves.contains(&Position::parse_str(pos).unwrap()));
+        }
+        assert!(game.get_board()[e4.idx].is_none());
+    }
+
+    // verify that the alpha-beta search finds a mate in one at every depth
+    #[test]
+    fn search_best_move_finds_mate() {
+        let game = game_with_pieces(&[
+            ("g6", PieceType::King, Colour::White),
+            ("b1", PieceType::Queen, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+            ("a7", PieceType::Pawn, Colour::Black),
+        ]);
+
+        for depth in 1..=3 {
+            let (from, to) = game.search_best_move(depth).unwrap();
+            let game_after_movement = game.clone_with_move(from, to);
+            assert!(game_after_movement.is_checkmate());
+            assert_eq!(game_after_movement.get_game_state(), GameState::GameOver);
+        }
+
+        // there is no move to search for once the game has ended
+        let game = game.clone_with_move(Position::parse_str("b1").unwrap(), Position::parse_str("b8").unwrap());
+        assert_eq!(game.search_best_move(2), None);
+    }
+
+    // verify that the search and counting functions do not panic on games that have ended while moves are still listed
+    #[test]
+    fn search_on_ended_games() {
+        // drawn by insufficient material
+        let drawn = Game::from_fen("8/8/4k3/8/8/8/4K3/4N3 w - - 0 1").unwrap();
+        assert_eq!(drawn.get_game_state(), GameState::Draw);
+
+        let mut resigned = Game::new();
+        resigned.resign(Colour::White);
+        assert_eq!(resigned.get_game_state(), GameState::GameOver);
+
+        for game in [drawn, resigned] {
+            let moves = game.all_legal_moves();
+            assert!(!moves.is_empty());
+            assert_eq!(game.perft(1), moves.len() as u64);
+            assert!(game.perft(2) > 0);
+            assert!(game.node_count(2) > game.perft(2));
+            assert_eq!(game.evaluate_all_moves(1).len(), moves.len());
+            assert!(game.best_move_with_limit(100).0.is_some());
+            game.cct_moves();
+            game.moves_defending(moves[0].1);
+            // the game itself is left as it was
+            assert!(game.game_result().is_some());
+        }
+
+        assert_eq!(Game::new().perft(2), 400);
+        let mut resigned = Game::new();
+        resigned.resign(Colour::Black);
+        assert_eq!(resigned.perft(2), 400);
+    }
+
+    // verify that a tiny node limit still returns a legal move and respects the limit
+    #[test]
+    fn best_move_with_tiny_limit() {
+        let game = Game::new();
+        let legal_moves = game.all_legal_moves();
+
+        for max_nodes in [0, 1, 5] {
+            let (best_move, nodes) = game.best_move_with_limit(max_nodes);
+            assert!(legal_moves.contains(&best_move.unwrap()));
+            assert!(nodes <= max_nodes);
+        }
+    }
+
+    // verify that the search finds a mate in one
+    #[test]
+    fn best_move_with_limit_finds_mate() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a1", PieceType::Rook, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+            ("g7", PieceType::Pawn, Colour::Black),
+            ("h7", PieceType::Pawn, Colour::Black),
+        ]);
+
+        let (best_move, nodes) = game.best_move_with_limit(10_000);
+        assert_eq!(
+            best_move,
+            Some((
+                Position::parse_str("a1").unwrap(),
+                Position::parse_str("a8").unwrap()
+            ))
+        );
+        assert!(nodes <= 10_000);
+    }
+
+    // verify the piece codes of the starting position
+    #[test]
+    fn piece_codes_at_start() {
+        let codes = Game::new().piece_codes();
+
+        assert_eq!(codes[0], 3); // white rook on a1
+        assert_eq!(codes[1], 4); // white knight on b1
+        assert_eq!(codes[2], 5); // white bishop on c1
+        assert_eq!(codes[12], 6); // white pawn on e2
+        assert_eq!(codes[28], 0); // e4 is empty
+        assert_eq!(codes[52], 12); // black pawn on e7
+        assert_eq!(codes[57], 10); // black knight on b8
+        assert_eq!(codes[63], 9); // black rook on h8
+        assert_eq!(codes.iter().filter(|code| **code == 0).count(), 32);
+    }
+
+    // verify the metadata of a capturing, checking move
+    #[test]
+    fn move_detailed_capture_and_check() {
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a1", PieceType::Rook, Colour::White),
+            ("a8", PieceType::Knight, Colour::Black),
+            ("h8", PieceType::King, Colour::Black),
+            ("g7", PieceType::Pawn, Colour::Black),
+        ]);
+
+        let result = game.make_move_detailed("a1", "a8").unwrap();
+        assert_eq!(result.state, GameState::Check);
+        assert_eq!(
+            result.captured,
+            Some(Piece {
+                piece_type: PieceType::Knight,
+                colour: Colour::Black
+            })
+        );
+        assert!(result.was_check);
+        assert_eq!(result.was_castle, None);
+        assert!(!result.was_en_passant);
+        assert_eq!(result.san, "Rxa8+");
+        assert_eq!(game.get_game_state(), GameState::Check);
+
+        // illegal moves leave the game untouched
+        assert!(game.make_move_detailed("a8", "b7").is_err());
+        assert_eq!(game.get_active_colour(), Colour::Black);
+    }
+
+    // verify the metadata of a quiet move
+    #[test]
+    fn move_detailed_quiet_move() {
+        let mut game = Game::new();
+
+        let result = game.make_move_detailed("g1", "f3").unwrap();
+        assert_eq!(result.state, GameState::InProgress);
+        assert_eq!(result.captured, None);
+        assert!(!result.was_check);
+        assert_eq!(result.san, "Nf3");
+    }
+
+    // verify that no move is described when the clock has run out, since the move is not made
+    #[test]
+    fn move_detailed_after_timeout() {
+        let mut game = Game::new();
+        game.set_clock(Some(GameClock::new(Duration::from_millis(5), Duration::ZERO)));
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(game.make_move_detailed("e2", "e4"), Err(ChessError::NotPlayable(GameState::GameOver)));
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+        assert_eq!(game.game_result(), Some(GameResult::Timeout(Colour::Black)));
+        assert!(game.get_board()[Position::parse_str("e2").unwrap().idx].is_some());
+        assert!(game.make_move_detailed("d2", "d4").is_err());
+    }
+
+    // verify that legal positions are accepted and illegal positions are rejected
+    #[test]
+    fn legal_position_checking() {
+        assert!(Game::new().is_legal_position());
+
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("a2", PieceType::Pawn, Colour::White),
+        ]);
+        assert!(game.is_legal_position());
+
+        // pawn on the first row
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("a1", PieceType::Pawn, Colour::White),
+        ]);
+        assert!(!game.is_legal_position());
+
+        // pawn on the last row
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("h8", PieceType::Pawn, Colour::Black),
+        ]);
+        assert!(!game.is_legal_position());
+
+        // two white kings
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("d1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+        ]);
+        assert!(!game.is_legal_position());
+
+        // no black king
+        let game = game_with_pieces(&[("e1", PieceType::King, Colour::White)]);
+        assert!(!game.is_legal_position());
+
+        // black is in check while it is white's turn
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e2", PieceType::Rook, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+        ]);
+        assert!(!game.is_legal_position());
+    }
+
+    // verify that legal moves can be restricted to a set of target squares
+    #[test]
+    fn legal_moves_into_center() {
+        let mut game = Game::new();
+        let center: Vec<Position> = ["d4", "e4", "d5", "e5"]
+            .iter()
+            .map(|pos| Position::parse_str(pos).unwrap())
+            .collect();
+
+        let moves = game.legal_moves_into(&center);
+        assert_eq!(moves.len(), 2);
+        assert!(moves.contains(&(
+            Position::parse_str("d2").unwrap(),
+            Position::parse_str("d4").unwrap()
+        )));
+        assert!(moves.contains(&(
+            Position::parse_str("e2").unwrap(),
+            Position::parse_str("e4").unwrap()
+        )));
+
+        // after 1. Nc3 d6 the knight can reach the center as well
+        game.make_move("b1", "c3").unwrap();
+        game.make_move("d7", "d6").unwrap();
+        let moves = game.legal_moves_into(&center);
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|(_, to)| center.contains(to)));
+    }
+
+    // verify the node count from the starting position
+    // there are 20 positions after white's first move and 400 after black's reply
+    #[test]
+    fn node_count_from_start() {
+        let game = Game::new();
+
+        assert_eq!(game.node_count(0), 1);
+        assert_eq!(game.node_count(1), 1 + 20);
+        assert_eq!(game.node_count(2), 1 + 20 + 400);
+    }
+
+    // verify that single moves can be validated, and that pinned pieces cannot leave the pin
+    #[test]
+    fn move_legality_with_pin() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e2", PieceType::Rook, Colour::White),
+            ("e8", PieceType::Rook, Colour::Black),
+            ("a8", PieceType::King, Colour::Black),
+        ]);
+
+        // the rook is pinned but may move along the pin
+        assert!(game.is_move_legal(
+            Position::parse_str("e2").unwrap(),
+            Position::parse_str("e5").unwrap()
+        ));
+        assert!(game.is_move_legal(
+            Position::parse_str("e2").unwrap(),
+            Position::parse_str("e8").unwrap()
+        ));
+        assert!(!game.is_move_legal(
+            Position::parse_str("e2").unwrap(),
+            Position::parse_str("a2").unwrap()
+        ));
+
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e2", PieceType::Bishop, Colour::White),
+            ("e8", PieceType::Rook, Colour::Black),
+            ("a8", PieceType::King, Colour::Black),
+        ]);
+
+        // the bishop is pinned and cannot move at all
+        assert!(!game.is_move_legal(
+            Position::parse_str("e2").unwrap(),
+            Position::parse_str("d3").unwrap()
+        ));
+
+        // moves by the wrong colour or from empty squares are not legal
+        assert!(!game.is_move_legal(
+            Position::parse_str("e8").unwrap(),
+            Position::parse_str("e7").unwrap()
+        ));
+        assert!(!game.is_move_legal(
+            Position::parse_str("d4").unwrap(),
+            Position::parse_str("d5").unwrap()
+        ));
+    }
+
+    // verify that the kings start on the e-file and the queens on the d-file
+    #[test]
+    fn kings_and_queens_start_on_correct_squares() {
+        let game = Game::new();
+        let board = game.get_board();
+
+        assert_eq!(
+            board[4],
+            Some(Piece {
+                piece_type: PieceType::King,
+                colour: Colour::White
+            })
+        );
+        assert_eq!(
+            board[3],
+            Some(Piece {
+                piece_type: PieceType::Queen,
+                colour: Colour::White
+            })
+        );
+        assert_eq!(
+            board[60],
+            Some(Piece {
+                piece_type: PieceType::King,
+                colour: Colour::Black
+            })
+        );
+        assert_eq!(
+            board[59],
+            Some(Piece {
+                piece_type: PieceType::Queen,
+                colour: Colour::Black
+            })
+        );
+    }
+
+    // verify that promotion distances are counted in the direction of each colour
+    #[test]
+    fn pawn_promotion_distances_per_colour() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("a7", PieceType::Pawn, Colour::White),
+            ("c2", PieceType::Pawn, Colour::White),
+            ("h7", PieceType::Pawn, Colour::Black),
+        ]);
+
+        let white_distances = game.pawn_promotion_distances(Colour::White);
+        assert_eq!(white_distances.len(), 2);
+        assert!(white_distances.contains(&(Position::parse_str("a7").unwrap(), 1)));
+        assert!(white_distances.contains(&(Position::parse_str("c2").unwrap(), 6)));
+
+        assert_eq!(
+            game.pawn_promotion_distances(Colour::Black),
+            vec![(Position::parse_str("h7").unwrap(), 6)]
+        );
+    }
+
+    // verify that a knight in the middle of the board can make all eight jumps
+    #[test]
+    fn knight_has_eight_jumps() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("d4", PieceType::Knight, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+
+        let moves = game.get_possible_moves(Position::parse_str("d4").unwrap(), 0);
+        assert_eq!(moves.len(), 8);
+        for pos in ["b3", "b5", "c2", "c6", "e2", "e6", "f3", "f5"] {
+            assert!(moves.contains(&Position::parse_str(pos).unwrap()));
+        }
+    }
+
+    // verify the rule of the square on positions where the king just barely catches or misses the pawn
+    #[test]
+    fn king_catches_pawn_by_rule_of_the_square() {
+        let mut game = game_with_pieces(&[
+            ("h1", PieceType::King, Colour::White),
+            ("a5", PieceType::Pawn, Colour::White),
+            ("e5", PieceType::King, Colour::Black),
+        ]);
+        let pawn = Position::parse_str("a5").unwrap();
+
+        // with black to move, the king on e5 can step into the square a5-d5-d8-a8, but the king on f5 cannot
+        game.active_colour = Colour::Black;
+        assert!(game.king_can_catch_pawn(Position::parse_str("e5").unwrap(), pawn, Colour::White));
+        assert!(!game.king_can_catch_pawn(Position::parse_str("f5").unwrap(), pawn, Colour::White));
+
+        // with white to move, the king must already be in the square
+        game.active_colour = Colour::White;
+        assert!(!game.king_can_catch_pawn(Position::parse_str("e5").unwrap(), pawn, Colour::White));
+        assert!(game.king_can_catch_pawn(Position::parse_str("d5").unwrap(), pawn, Colour::White));
+
+        // a pawn on its first row is one move closer thanks to the double step
+        let pawn = Position::parse_str("h7").unwrap();
+        assert!(game.king_can_catch_pawn(Position::parse_str("b2").unwrap(), pawn, Colour::Black));
+        assert!(!game.king_can_catch_pawn(Position::parse_str("a2").unwrap(), pawn, Colour::Black));
+    }
+
+    // verify that a knight blocked by a friendly piece on one jump still finds its other jumps
+    #[test]
+    fn knight_jumps_past_blocked_square() {
+        let game = Game::new();
+
+        // the knight on b1 cannot jump to d2 where the own pawn stands, but can still jump to a3 and c3
+        let moves = game.get_possible_moves(Position::parse_str("b1").unwrap(), 0);
+        assert_eq!(moves.len(), 2);
+        assert!(moves.contains(&Position::parse_str("a3").unwrap()));
+        assert!(moves.contains(&Position::parse_str("c3").unwrap()));
+    }
+
+    // verify that the debug dump contains the state of the game
+    #[test]
+    fn debug_dump_contains_state() {
+        let mut game = Game::new();
+        assert!(game.debug_dump().contains("moves: \n"));
+        game.make_move("e2", "e4").unwrap();
+        game.make_move("e7", "e5").unwrap();
+
+        let dump = game.debug_dump();
+        assert!(dump.contains(&game.to_fen()));
+        assert!(dump.contains("state: InProgress"));
+        assert!(dump.contains("active colour: White"));
+        assert!(dump.contains("last moved to: e5"));
+        assert!(dump.contains("moves: e2e4 e7e5\n"));
+        assert!(dump.contains(&game.to_string()));
+    }
+
+    // verify that positions outside of the board are rejected
+    #[test]
+    fn position_bounds_checked() {
+        assert!(Position::new(8, 0).is_err());
+        assert!(Position::new(0, 8).is_err());
+
+        let pos = Position::new(7, 7).unwrap();
+        assert_eq!(pos.idx, 63);
+    }
+
+    // verify that misplaced pawns on the first or last row get no moves rather than causing a panic
+    #[test]
+    fn misplaced_pawns_have_no_moves() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("a1", PieceType::Pawn, Colour::White),
+            ("b8", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::Pawn, Colour::Black),
+            ("g1", PieceType::Pawn, Colour::Black),
+        ]);
+
+        for pos in ["a1", "b8", "h8", "g1"] {
+            assert!(game
+                .get_possible_moves(Position::parse_str(pos).unwrap(), 0)
+                .is_empty());
+        }
+    }
+
+    // verify that moves defending a hanging piece are found
+    #[test]
+    fn moves_defending_hanging_piece() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a1", PieceType::Rook, Colour::White),
+            ("e5", PieceType::Knight, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        let target = Position::parse_str("e5").unwrap();
+
+        let moves = game.moves_defending(target);
+        let a1 = Position::parse_str("a1").unwrap();
+        assert!(moves.contains(&(a1, Position::parse_str("a5").unwrap())));
+        assert!(!moves.contains(&(a1, Position::parse_str("a2").unwrap())));
+        // the knight itself moving away does not defend the square it left
+        assert!(moves.iter().all(|(from, _)| *from != target));
+        // every found move does defend the knight
+        for (from, to) in moves {
+            let mut game_after_movement = game.clone();
+            game_after_movement.make_move_pos(from, to).unwrap();
+            assert!(game_after_movement.attacks(to, target));
+        }
+
+        // a pawn promoting on the last row defends like the queen it becomes
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("b7", PieceType::Pawn, Colour::White),
+            ("e8", PieceType::Rook, Colour::White),
+            ("h1", PieceType::King, Colour::Black),
+        ]);
+        let moves = game.moves_defending(Position::parse_str("e8").unwrap());
+        assert!(moves.contains(&(Position::parse_str("b7").unwrap(), Position::parse_str("b8").unwrap())));
+    }
+
+    // verify the FEN export of the starting position and after a move
+    #[test]
+    fn fen_export() {
+        let mut game = Game::new();
+        assert!(game
+            .to_fen()
+            .starts_with("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w"));
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"
+        );
+
+        game.make_move("e2", "e4").unwrap();
+        assert!(game
+            .to_fen()
+            .starts_with("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b"));
+
+        game.make_move("g8", "f6").unwrap();
+        assert!(game
+            .to_fen()
+            .starts_with("rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w"));
+    }
+
+    // verify that captures and quiet moves partition the legal moves
+    #[test]
+    fn captures_and_quiet_moves_partition_legal_moves() {
+        let mut game = Game::new();
+        for (from, to) in [("e2", "e4"), ("d7", "d5"), ("b1", "c3"), ("g8", "f6")] {
+            game.make_move(from, to).unwrap();
+        }
+
+        let captures = game.legal_captures();
+        let quiet_moves = game.legal_quiet_moves();
+        let all_moves = game.all_legal_moves();
+
+        // e4xd5 and Nc3xd5
+        assert_eq!(captures.len(), 2);
+        assert!(captures.iter().all(|mv| !quiet_moves.contains(mv)));
+        assert_eq!(captures.len() + quiet_moves.len(), all_moves.len());
+        assert!(all_moves
+            .iter()
+            .all(|mv| captures.contains(mv) || quiet_moves.contains(mv)));
+    }
+
+    // verify that positions survive a round trip through FEN
+    #[test]
+    fn fen_round_trip() {
+        let mut game = Game::new();
+        let mut positions = vec![game.to_fen()];
+        for (from, to) in [("e2", "e4"), ("c7", "c5"), ("g1", "f3"), ("d7", "d6")] {
+            game.make_move(from, to).unwrap();
+            positions.push(game.to_fen());
+        }
+        positions.push(String::from("8/8/4k3/8/8/8/4P3/4K3 w - - 0 1"));
+        positions.push(String::from("7k/6pp/8/8/8/8/8/R3K3 b - - 0 1"));
+
+        for fen in positions {
+            let loaded = Game::from_fen(&fen).unwrap();
+            assert_eq!(loaded.to_fen(), fen);
+        }
+
+        // the loaded game is playable
+        let mut loaded = Game::from_fen(&game.to_fen()).unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.get_active_colour(), Colour::White);
+        assert!(loaded.make_move("d2", "d4").is_ok());
+    }
+
+    // verify that the state is computed for a loaded position
+    #[test]
+    fn fen_import_computes_state() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        assert_eq!(game.get_game_state(), GameState::Check);
+
+        let game = Game::from_fen("R6k/6pp/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+    }
+
+    // verify that malformed FEN is rejected
+    #[test]
+    fn fen_import_rejects_malformed_input() {
+        // wrong field count
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - -").is_err());
+        // too few rows
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1").is_err());
+        // row describing too many squares
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1").is_err());
+        assert!(Game::from_fen("rnbqkbnrr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1").is_err());
+        // row describing too few squares
+        assert!(Game::from_fen("rnbqkbn/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1").is_err());
+        // illegal character
+        assert!(Game::from_fen("rnbqkbnr/ppppxppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1").is_err());
+        // invalid active colour
+        assert!(Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x - - 0 1").is_err());
+        // two white kings
+        assert!(Game::from_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").is_err());
+        // no black king
+        assert!(Game::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").is_err());
+        // pawn on the last row
+        assert!(Game::from_fen("P3k3/8/8/8/8/8/8/4K3 w - - 0 1").is_err());
+    }
+
+    // verify that a board survives a round trip through the Display format, and that malformed boards are rejected
+    #[test]
+    fn ascii_round_trip() {
+        let game = Game::from_ascii(&Game::new().to_string()).unwrap();
+        assert_eq!(game.get_board(), Game::new().get_board());
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        let moved = Game::new_from_moves(&[("e2", "e4"), ("g8", "f6"), ("d1", "h5")]).unwrap();
+        assert_eq!(Game::from_ascii(&moved.to_string()).unwrap().get_board(), moved.get_board());
+
+        // the state is computed from the position
+        let game = Game::from_ascii(
+            "
+            | bR  *   *   *   wK  *   *   *  |
+            | *   *   *   *   *   *   *   *  |
+            | *   *   *   *   *   *   *   *  |
+            | *   *   *   *   *   *   *   *  |
+            | *   *   *   *   *   *   *   *  |
+            | *   *   *   *   *   *   *   *  |
+            | *   *   *   *   *   *   bP  bP |
+            | *   *   *   *   *   *   *   bK |
+            ",
+        )
+        .unwrap();
+        assert_eq!(game.get_game_state(), GameState::Check);
+        assert_eq!(game.get_active_colour(), Colour::White);
+
+        let start = Game::new().to_string();
+        // too few rows
+        assert!(Game::from_ascii(&start.replacen("| wR  wKn wB  wQ  wK  wB  wKn wR |", "", 1)).is_err());
+        // too few squares in a row
+        assert!(Game::from_ascii(&start.replacen("wR  wKn", "wKn", 1)).is_err());
+        // invalid squares
+        assert!(Game::from_ascii(&start.replacen("wKn", "wN ", 1)).is_err());
+        assert!(Game::from_ascii(&start.replacen("wKn", "xKn", 1)).is_err());
+        // two white kings
+        assert!(Game::from_ascii(&start.replacen("wQ ", "wK ", 1)).is_err());
+    }
+
+    // verify that a game can be set up from a list of moves in one call
+    #[test]
+    fn new_from_moves_sets_up_position() {
+        let game = Game::new_from_moves(&[("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")]).unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.get_active_colour(), Colour::White);
+        assert_eq!(game.eco_code(), Some("C44"));
+
+        // an illegal move returns its error
+        assert!(Game::new_from_moves(&[("e2", "e4"), ("e2", "e3")]).is_err());
+    }
+
+    // verify that a side with only a king and a knight cannot mate, while the opponent with pawns can
+    #[test]
+    fn cannot_mate_with_lone_knight() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("g1", PieceType::Knight, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+            ("a7", PieceType::Pawn, Colour::Black),
+            ("h7", PieceType::Pawn, Colour::Black),
+        ]);
+        assert!(game.cannot_mate(Colour::White));
+        assert!(!game.cannot_mate(Colour::Black));
+
+        let game = Game::new();
+        assert!(!game.cannot_mate(Colour::White));
+        assert!(!game.cannot_mate(Colour::Black));
+    }
+
+    // verify that checks come before captures, captures before threats, and that quiet moves are left out
+    #[test]
+    fn cct_moves_ordered_by_importance() {
+        let game = game_with_pieces(&[
+            ("g1", PieceType::King, Colour::White),
+            ("d1", PieceType::Queen, Colour::White),
+            ("c1", PieceType::Bishop, Colour::White),
+            ("g8", PieceType::King, Colour::Black),
+            ("d4", PieceType::Rook, Colour::Black),
+            ("h6", PieceType::Pawn, Colour::Black),
+        ]);
+        let moves = game.cct_moves();
+        let index_of = |from: &str, to: &str| {
+            let mv = (Position::parse_str(from).unwrap(), Position::parse_str(to).unwrap());
+            moves.iter().position(|m| *m == mv)
+        };
+
+        let check = index_of("d1", "b3").unwrap();
+        let rook_capture = index_of("d1", "d4").unwrap();
+        let pawn_capture = index_of("c1", "h6").unwrap();
+        let threat = index_of("c1", "e3").unwrap();
+        assert!(check < rook_capture);
+        assert!(rook_capture < pawn_capture);
+        assert!(pawn_capture < threat);
+        assert_eq!(index_of("g1", "h1"), None);
+    }
+
+    // verify that the coordinates of a position can be read back out
+    #[test]
+    fn position_accessors() {
+        let pos = Position::parse_str("e1").unwrap();
+        assert_eq!(pos.row(), 0);
+        assert_eq!(pos.col(), 4);
+        assert_eq!(pos.idx(), 4);
+
+        let pos = Position::parse_str("h8").unwrap();
+        assert_eq!((pos.row(), pos.col(), pos.idx()), (7, 7, 63));
+    }
+
+    // verify that with_move leaves the original game untouched
+    #[test]
+    fn with_move_does_not_mutate() {
+        let game = Game::new();
+        let fen_before = game.to_fen();
+
+        let game_after_movement = game
+            .with_move(Position::parse_str("e2").unwrap(), Position::parse_str("e4").unwrap())
+            .unwrap();
+        assert_eq!(game.to_fen(), fen_before);
+        assert_eq!(game.get_active_colour(), Colour::White);
+        assert!(game.clone().undo_move().is_err());
+
+        assert_eq!(game_after_movement.get_active_colour(), Colour::Black);
+        assert!(game_after_movement.get_board()[Position::parse_str("e4").unwrap().idx].is_some());
+        assert!(game_after_movement.get_board()[Position::parse_str("e2").unwrap().idx].is_none());
+
+        // an illegal move is reported and leaves the game untouched too
+        assert!(game
+            .with_move(Position::parse_str("e2").unwrap(), Position::parse_str("e5").unwrap())
+            .is_err());
+        assert_eq!(game.to_fen(), fen_before);
+    }
+
+    // verify that undoing every move restores the starting position
+    #[test]
+    fn undo_move_restores_position() {
+        let mut game = Game::new();
+        assert!(game.undo_move().is_err());
+
+        let moves = [("e2", "e4"), ("e7", "e5"), ("d1", "h5"), ("b8", "c6"), ("h5", "f7")];
+        for (from, to) in moves {
+            game.make_move(from, to).unwrap();
+        }
+        assert_eq!(game.get_game_state(), GameState::Check);
+
+        game.undo_move().unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.get_active_colour(), Colour::White);
+        assert!(game.get_board()[Position::parse_str("f7").unwrap().idx].is_some());
+
+        for _ in 1..moves.len() {
+            game.undo_move().unwrap();
+        }
+        assert_eq!(game.get_board(), Game::new().get_board());
+        assert_eq!(game.get_active_colour(), Colour::White);
+        assert!(game.undo_move().is_err());
+    }
+
+    // verify that a promotion is taken back together with the pawn move
+    #[test]
+    fn undo_move_takes_back_promotion() {
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a7", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        let board_before = *game.get_board();
+
+        game.make_move("a7", "a8").unwrap();
+        game.set_promotion(String::from("rook")).unwrap();
+        game.undo_move().unwrap();
+
+        assert_eq!(game.get_board(), &board_before);
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.get_active_colour(), Colour::White);
+    }
+
+    // verify that the top rated move is the move chosen by the search
+    #[test]
+    fn evaluate_all_moves_best_first() {
+        let game = Game::new_from_moves(&[
+            ("e2", "e4"),
+            ("e7", "e5"),
+            ("d1", "h5"),
+            ("b8", "c6"),
+            ("f1", "c4"),
+            ("g8", "f6"),
+        ])
+        .unwrap();
+
+        let evaluated_moves = game.evaluate_all_moves(1);
+        assert_eq!(evaluated_moves.len(), game.legal_captures().len() + game.legal_quiet_moves().len());
+        assert!(evaluated_moves.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let (best_move, _) = game.best_move_with_limit(u64::MAX);
+        assert_eq!(Some(evaluated_moves[0].0), best_move);
+        assert_eq!(
+            evaluated_moves[0].0,
+            (Position::parse_str("h5").unwrap(), Position::parse_str("f7").unwrap())
+        );
+    }
+
+    // verify that single squares can be read from the board
+    #[test]
+    fn get_piece_at_reads_square() {
+        let game = Game::new();
+        let piece = game.get_piece_at(Position::parse_str("e1").unwrap());
+        assert_eq!(
+            piece,
+            Some(Piece {
+                piece_type: PieceType::King,
+                colour: Colour::White
+            })
+        );
+        assert_eq!(game.get_piece_at(Position::parse_str("e4").unwrap()), None);
+    }
+
+    // verify that a draw can be claimed after 50 moves by each player without a capture or a pawn move
+    #[test]
+    fn fifty_move_rule_is_claimable() {
+        let mut game = Game::from_fen("1n2k1n1/pppppppp/8/8/8/8/PPPPPPPP/1N2K1N1 w - - 0 1").unwrap();
+        let mut seen_boards = vec![*game.get_board()];
+
+        // Make quiet piece moves that never repeat a position, such that no other draw rule applies
+        for halfmove in 0..100 {
+            assert_eq!(game.game_result(), None, "game over after {} halfmoves", halfmove);
+            let (from, to) = game
+                .legal_quiet_moves()
+                .into_iter()
+                .find(|(from, to)| {
+                    game.get_board()[from.idx].unwrap().piece_type != PieceType::Pawn
+                        && !seen_boards.contains(game.clone_with_move(*from, *to).get_board())
+                })
+                .unwrap();
+            if halfmove < 99 {
+                assert_eq!(game.claim_fifty_move_draw(), Err(ChessError::DrawNotClaimable));
+            }
+            game.make_move_pos(from, to).unwrap();
+            seen_boards.push(*game.get_board());
+        }
+        // the game goes on until the draw is claimed
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert!(game.to_fen().ends_with(" 100 51"));
+        assert_eq!(game.claim_fifty_move_draw(), Ok(GameState::Draw));
+        assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::FiftyMoveRule)));
+        assert_eq!(game.claim_fifty_move_draw(), Err(ChessError::NotPlayable(GameState::Draw)));
+
+        // a pawn move resets the clock
+        let mut game = Game::from_fen("4k3/4p3/8/8/8/8/8/4K3 b - - 99 1").unwrap();
+        game.make_move("e7", "e6").unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        game.make_move("e1", "d1").unwrap();
+        assert!(game.to_fen().ends_with(" 1 2"));
+        assert_eq!(game.claim_fifty_move_draw(), Err(ChessError::DrawNotClaimable));
+
+        let mut game = Game::from_fen("4k3/4p3/8/8/8/8/8/4K3 w - - 99 1").unwrap();
+        game.make_move("e1", "d1").unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.claim_fifty_move_draw(), Ok(GameState::Draw));
+    }
+
+    // verify that the game ends without a claim after 75 moves by each player without a capture or a pawn move
+    #[test]
+    fn seventy_five_move_rule_ends_game() {
+        let mut game = Game::from_fen("4k3/4p3/8/8/8/8/8/4K3 w - - 148 1").unwrap();
+        game.make_move("e1", "d1").unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        game.make_move("e8", "d8").unwrap();
+        assert_eq!(game.get_game_state(), GameState::Draw);
+        assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::SeventyFiveMoveRule)));
+        assert!(game.make_move("d1", "e1").is_err());
+
+        // taking back the last move takes back the draw
+        game.undo_move().unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        // a checkmate on the last move takes precedence
+        let mut game = Game::from_fen("7k/6pp/8/8/8/8/8/R3K3 w - - 149 1").unwrap();
+        game.make_move("a1", "a8").unwrap();
+        assert_eq!(game.game_result(), Some(GameResult::Checkmate(Colour::White)));
+    }
+
+    // verify that checkmate and stalemate are told apart
+    #[test]
+    fn game_result_distinguishes_endings() {
+        let game = Game::new();
+        assert_eq!(game.game_result(), None);
+
+        let game = Game::new_from_moves(&[
+            ("e2", "e4"),
+            ("e7", "e5"),
+            ("d1", "h5"),
+            ("b8", "c6"),
+            ("f1", "c4"),
+            ("g8", "f6"),
+            ("h5", "f7"),
+        ])
+        .unwrap();
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+        assert_eq!(game.game_result(), Some(GameResult::Checkmate(Colour::White)));
+
+        let mut game = game_with_pieces(&[
+            ("a1", PieceType::King, Colour::White),
+            ("c6", PieceType::Queen, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        game.make_move("c6", "g6").unwrap();
+        assert_eq!(game.get_game_state(), GameState::Draw);
+        assert_eq!(game.game_result(), Some(GameResult::Stalemate));
+    }
+
+    // verify that a position built from an index agrees with one built from its row and col
+    #[test]
+    fn position_from_idx_matches_row_and_col() {
+        let pos = Position::new_from_idx(9).unwrap();
+        assert_eq!((pos.row, pos.col, pos.idx), (1, 1, 9));
+        assert_eq!(pos, Position::new(1, 1).unwrap());
+
+        for idx in 0..64 {
+            let pos = Position::new_from_idx(idx).unwrap();
+            assert_eq!(pos, Position::new(pos.row, pos.col).unwrap());
+        }
+    }
+
+    // verify that shuffling knights back and forth lets a draw by threefold repetition be claimed
+    #[test]
+    fn threefold_repetition_is_claimable() {
+        let shuffle = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+        let mut game = Game::new();
+
+        // the starting position occurs for the second time
+        for (from, to) in shuffle {
+            game.make_move(from, to).unwrap();
+        }
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.claim_threefold_repetition(), Err(ChessError::DrawNotClaimable));
+
+        // and for the third time on the last move, which does not end the game by itself
+        for (from, to) in shuffle {
+            assert_eq!(game.get_game_state(), GameState::InProgress);
+            game.make_move(from, to).unwrap();
+        }
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        // taking back the last move also takes back the repetition
+        let mut taken_back = game.clone();
+        taken_back.undo_move().unwrap();
+        assert_eq!(taken_back.claim_threefold_repetition(), Err(ChessError::DrawNotClaimable));
+
+        assert_eq!(game.claim_threefold_repetition(), Ok(GameState::Draw));
+        assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::ThreefoldRepetition)));
+        assert_eq!(game.claim_threefold_repetition(), Err(ChessError::NotPlayable(GameState::Draw)));
+    }
+
+    // verify that the fifth occurrence of a position draws the game without a claim
+    #[test]
+    fn fivefold_repetition_draws() {
+        let shuffle = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+        let mut game = Game::new();
+
+        // the starting position occurs for the fifth time on the last move
+        for _ in 0..4 {
+            for (from, to) in shuffle {
+                assert_eq!(game.get_game_state(), GameState::InProgress);
+                game.make_move(from, to).unwrap();
+            }
+        }
+        assert_eq!(game.get_game_state(), GameState::Draw);
+        assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::FivefoldRepetition)));
+        assert!(game.make_move("b1", "c3").is_err());
+
+        // taking back the last move also takes back the repetition
+        game.undo_move().unwrap();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+        assert_eq!(game.game_result(), None);
+    }
+
+    // verify that positions where neither colour can checkmate are drawn
+    #[test]
+    fn insufficient_material_draws() {
+        for fen in [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",   // K vs K
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", // KB vs K
+            "4k3/8/8/8/8/8/8/1N2K3 b - - 0 1", // KN vs K
+            "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1", // KB vs KB, both on dark squares
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(game.get_game_state(), GameState::Draw, "{}", fen);
+            assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::InsufficientMaterial)));
+        }
+
+        for fen in [
+            "2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1", // KB vs KB on opposite square colours
+            "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1",  // KNN vs K
+            "4k3/4p3/8/8/8/8/8/4K3 w - - 0 1",   // KP vs K
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(game.get_game_state(), GameState::InProgress, "{}", fen);
+        }
+
+        // capturing the last pawn draws the game
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1").unwrap();
+        game.make_move("e1", "d2").unwrap();
+        assert_eq!(game.get_game_state(), GameState::Draw);
+    }
+
+    // verify that moves are written in SAN with disambiguation and check markers
+    #[test]
+    fn move_to_san_notation() {
+        let square = |s: &str| Position::parse_str(s).unwrap();
+
+        let game = Game::new();
+        assert_eq!(game.move_to_san(square("g1"), square("f3"), None), Ok(String::from("Nf3")));
+        assert_eq!(game.move_to_san(square("e2"), square("e4"), None), Ok(String::from("e4")));
+        assert!(game.move_to_san(square("e2"), square("e5"), None).is_err());
+        assert!(game.move_to_san(square("e2"), square("e4"), Some(PieceType::Queen)).is_err());
+
+        // knights on different files and on the same file
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("b1", PieceType::Knight, Colour::White),
+            ("f3", PieceType::Knight, Colour::White),
+            ("b3", PieceType::Knight, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+        ]);
+        assert_eq!(game.move_to_san(square("f3"), square("d2"), None), Ok(String::from("Nfd2")));
+        assert_eq!(game.move_to_san(square("b1"), square("d2"), None), Ok(String::from("N1d2")));
+        assert_eq!(game.move_to_san(square("f3"), square("g5"), None), Ok(String::from("Ng5")));
+
+        // checkmate
+        let game = Game::new_from_moves(&[
+            ("e2", "e4"),
+            ("e7", "e5"),
+            ("d1", "h5"),
+            ("b8", "c6"),
+            ("f1", "c4"),
+            ("g8", "f6"),
+        ])
+        .unwrap();
+        assert_eq!(game.move_to_san(square("h5"), square("f7"), None), Ok(String::from("Qxf7#")));
+
+        // promotion with check
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a7", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        assert_eq!(game.move_to_san(square("a7"), square("a8"), Some(PieceType::Queen)), Ok(String::from("a8=Q+")));
+        assert_eq!(game.move_to_san(square("a7"), square("a8"), Some(PieceType::Knight)), Ok(String::from("a8=N")));
+    }
+
+    // verify that a game can be played through SAN
+    #[test]
+    fn make_move_san_replays_opening() {
+        let mut game = Game::new();
+        for san in ["e4", "e5", "Nf3", "Nc6"] {
+            game.make_move_san(san).unwrap();
+        }
+        let expected = Game::new_from_moves(&[("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")]).unwrap();
+        assert_eq!(game.get_board(), expected.get_board());
+        assert_eq!(game.get_active_colour(), Colour::White);
+
+        assert!(game.make_move_san("Nf6").is_err());
+        assert!(game.make_move_san("Nxe5").is_ok());
+        assert!(game.make_move_san("O-O").is_err());
+    }
+
+    // verify that ambiguous and malformed SAN is rejected
+    #[test]
+    fn make_move_san_rejects_ambiguity() {
+        let pieces = [
+            ("e1", PieceType::King, Colour::White),
+            ("b1", PieceType::Knight, Colour::White),
+            ("f3", PieceType::Knight, Colour::White),
+            ("a7", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ];
+        let mut game = game_with_pieces(&pieces);
+        assert!(game.make_move_san("Nd2").unwrap_err().to_string().contains("ambiguous"));
+        assert!(game.make_move_san("Nxd2").is_err());
+        assert!(game.make_move_san("a8").is_err());
+        assert!(game.make_move_san("a8=K").is_err());
+        assert!(game.make_move_san("Zz9").is_err());
+        assert_eq!(game.get_board(), game_with_pieces(&pieces).get_board());
+
+        assert_eq!(game.make_move_san("Nbd2"), Ok(GameState::InProgress));
+        assert_eq!(game.get_piece_at(Position::parse_str("d2").unwrap()).unwrap().piece_type, PieceType::Knight);
+    }
+
+    // verify that moves can be constructed and applied, including promotions
+    #[test]
+    fn apply_move_with_move_struct() {
+        let mut game = Game::new();
+        let mv = Move::parse_str("e2", "e4").unwrap();
+        assert_eq!(mv, Move::new(Position::parse_str("e2").unwrap(), Position::parse_str("e4").unwrap()));
+        assert_eq!(game.apply_move(mv), Ok(GameState::InProgress));
+        assert_eq!(game.get_active_colour(), Colour::Black);
+        assert!(game.apply_move(Move::parse_str("e4", "e5").unwrap()).is_err());
+        assert!(Move::parse_str("e2", "e9").is_err());
+
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a7", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        let from = Position::parse_str("a7").unwrap();
+        let to = Position::parse_str("a8").unwrap();
+        assert!(game.apply_move(Move::new(from, to)).is_err());
+        game.apply_move(Move::new_promotion(from, to, PieceType::Rook)).unwrap();
+        assert_eq!(game.get_piece_at(to).unwrap().piece_type, PieceType::Rook);
+    }
+
+    // verify that perft matches the known values from the starting position
+    #[test]
+    fn perft_from_start() {
+        let game = Game::new();
+        assert_eq!(game.perft(0), 1);
+        assert_eq!(game.perft(1), 20);
+        assert_eq!(game.perft(2), 400);
+    }
+
+    // verify that perft 3 plies deep is correct
+    #[test]
+    fn perft_three_plies() {
+        assert_eq!(Game::new().perft(3), 8902);
+    }
+
+    // verify that perft 3 plies deep is fast, since finding legal moves no longer clones the game for every candidate move.
+    // Ignored by default since the timing depends on the machine, run it with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn perft_three_plies_is_fast() {
+        let game = Game::new();
+        let start = std::time::Instant::now();
+        assert_eq!(game.perft(3), 8902);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    // verify that the attacks found with bitboards are the squares where the piece could capture an enemy piece, in positions with
+    // blocked sliders, pawns of both colours, misplaced pawns and kings next to each other
+    #[test]
+    fn attacks_match_pseudo_legal_captures() {
+        let mut games: Vec<Game> = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w - - 0 1",
+            "8/8/4k3/3N4/4K3/8/8/8 b - - 0 1",
+        ]
+        .iter()
+        .map(|fen| Game::from_fen(fen).unwrap())
+        .collect();
+        games.push(game_with_pieces(&[
+            ("a1", PieceType::King, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+            ("a8", PieceType::Pawn, Colour::White),
+            ("h1", PieceType::Pawn, Colour::Black),
+            ("b1", PieceType::Pawn, Colour::White),
+        ]));
+        for game in games {
+            let fen = game.to_fen();
+            for from in 0..64 {
+                let piece = match game.board[from] {
+                    None => continue,
+                    Some(piece) => piece,
+                };
+                for target in (0..64).filter(|target| *target != from) {
+                    let mut game_with_enemy = game.clone();
+                    game_with_enemy.set_square(target, Some(Piece::new(PieceType::Pawn, piece.colour.opposite())));
+                    let from_pos = Position::new_from_idx(from).unwrap();
+                    let target_pos = Position::new_from_idx(target).unwrap();
+                    let can_capture = game_with_enemy.pseudo_legal_moves(from_pos).contains(&target_pos);
+                    assert_eq!(game.attacks(from_pos, target_pos), can_capture, "{} {} {}", fen, from_pos, target_pos);
+                }
+            }
+        }
+    }
+
+    // verify that the bitboards stored in the game follow the board through moves, promotions, undos and set-up positions
+    #[test]
+    fn bitboards_follow_board() {
+        let assert_in_sync = |game: &Game| assert_eq!(game.bitboards, Bitboards::from_board(&game.board));
+
+        let mut game = Game::new_from_moves(&[("e2", "e4"), ("d7", "d5"), ("e4", "d5")]).unwrap();
+        assert_in_sync(&game);
+        game.undo_move().unwrap();
+        assert_in_sync(&game);
+
+        let mut game = game_with_pieces(&[
+            ("a1", PieceType::King, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+            ("b7", PieceType::Pawn, Colour::White),
+            ("c8", PieceType::Rook, Colour::Black),
+        ]);
+        assert_in_sync(&game);
+        game.make_move("b7", "c8").unwrap();
+        assert_in_sync(&game);
+        game.set_promotion_piece(PieceType::Queen).unwrap();
+        assert_in_sync(&game);
+        game.undo_move().unwrap();
+        assert_in_sync(&game);
+
+        game.place_piece(Position::parse_str("c8").unwrap(), None);
+        game.place_piece(Position::parse_str("d4").unwrap(), Some(Piece::new(PieceType::Knight, Colour::Black)));
+        assert_in_sync(&game);
+        assert_in_sync(&Game::empty());
+        assert_in_sync(&Game::from_fen("8/8/4k3/3N4/4K3/8/8/8 b - - 0 1").unwrap());
+    }
+
+    // verify that perft gives the same counts as before move legality was checked with bitboards, in positions with
+    // pins, checks and promotions. The counts differ from published values since castling and en passant are not implemented.
+    #[test]
+    fn perft_matches_board_array_implementation() {
+        let positions = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1", 4, 197281),
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w - - 0 1", 3, 86585),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4, 43087),
+            ("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1", 3, 3253),
+        ];
+
+        for (fen, depth, nodes) in positions {
+            assert_eq!(Game::from_fen(fen).unwrap().perft(depth), nodes, "{}", fen);
+        }
+    }
+
+    // verify that a game survives a round trip through JSON
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut game = Game::new_from_moves(&[("e2", "e4"), ("e7", "e5"), ("g1", "f3")]).unwrap();
+
+        let json = serde_json::to_string(&game).unwrap();
+        let mut loaded: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.get_active_colour(), Colour::Black);
+        assert_eq!(loaded.get_game_state(), GameState::InProgress);
+
+        // the history is kept too, such that moves can still be taken back
+        loaded.undo_move().unwrap();
+        game.undo_move().unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+
+        let piece: Piece = serde_json::from_str(&serde_json::to_string(&game.get_board()[4]).unwrap()).unwrap();
+        assert_eq!(piece.piece_type, PieceType::King);
+        assert!(serde_json::from_str::<Game>("{}").is_err());
+    }
+
+    // verify that a saved game can be loaded and still take back the moves made before saving
+    #[cfg(feature = "serde")]
+    #[test]
+    fn save_and_load_state_keeps_history() {
+        let mut game = Game::new_from_moves(&[("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")]).unwrap();
+
+        let mut loaded = Game::load_state(&game.save_state()).unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.to_fen(), game.to_fen());
+
+        for _ in 0..4 {
+            loaded.undo_move().unwrap();
+            game.undo_move().unwrap();
+            assert_eq!(loaded.get_board(), game.get_board());
+            assert_eq!(loaded.to_fen(), game.to_fen());
+        }
+        assert_eq!(loaded.undo_move(), Err(ChessError::NothingToUndo));
+        assert_eq!(loaded.get_board(), Game::new().get_board());
+
+        assert!(matches!(Game::load_state("not a saved game"), Err(ChessError::ParseError(_))));
+    }
+
+    // verify that a promotion keeps the colour of the pawn and passes the turn exactly once
+    #[test]
+    fn promotion_passes_turn_once() {
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("a7", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        game.make_move("a7", "a8").unwrap();
+        assert_eq!(game.set_promotion(String::from("queen")), Ok(GameState::Check));
+        assert_eq!(game.get_active_colour(), Colour::Black);
+        assert_eq!(
+            game.get_piece_at(Position::parse_str("a8").unwrap()),
+            Some(Piece {
+                piece_type: PieceType::Queen,
+                colour: Colour::White
+            })
+        );
+
+        // Black can answer, and the turn then passes back to White
+        game.make_move("h8", "h7").unwrap();
+        assert_eq!(game.get_active_colour(), Colour::White);
+    }
+
+    // verify that resigning ends the game in favour of the opponent
+    #[test]
+    fn resign_ends_game() {
+        let mut game = Game::new();
+        assert_eq!(game.resign(Colour::White), GameState::GameOver);
+        assert_eq!(game.game_result(), Some(GameResult::Resignation(Colour::Black)));
+        assert!(game.make_move("e2", "e4").is_err());
+
+        // resigning a finished game changes nothing
+        assert_eq!(game.resign(Colour::Black), GameState::GameOver);
+        assert_eq!(game.game_result(), Some(GameResult::Resignation(Colour::Black)));
+
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.resign(Colour::White), GameState::Draw);
+        assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::InsufficientMaterial)));
+    }
+
+    // verify that a draw can be offered, declined by moving, and accepted
+    #[test]
+    fn draw_by_agreement() {
+        let mut game = Game::new();
+        assert!(game.accept_draw().is_err());
+
+        // White offers and moves, Black declines by moving
+        game.offer_draw();
+        assert_eq!(game.get_draw_offer(), Some(Colour::White));
+        game.make_move("e2", "e4").unwrap();
+        assert_eq!(game.get_draw_offer(), Some(Colour::White));
+        game.make_move("e7", "e5").unwrap();
+        assert_eq!(game.get_draw_offer(), None);
+        assert!(game.accept_draw().is_err());
+
+        // White offers again and Black accepts
+        game.offer_draw();
+        game.make_move("g1", "f3").unwrap();
+        assert_eq!(game.accept_draw(), Ok(GameState::Draw));
+        assert_eq!(game.game_result(), Some(GameResult::DrawByAgreement));
+        assert!(game.make_move("b8", "c6").is_err());
+        assert!(game.accept_draw().is_err());
+    }
+
+    // verify that the board is rendered with Unicode glyphs
+    #[test]
+    fn unicode_output() {
+        let game = Game::new();
+        let expected = "♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖
+♙ ♙ ♙ ♙ ♙ ♙ ♙ ♙
+· · · · · · · ·
+· · · · · · · ·
+· · · · · · · ·
+· · · · · · · ·
+♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟
+♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜";
+        assert_eq!(game.to_unicode_string(), expected);
+    }
+
+    // verify that the board can be rendered from Black's perspective
+    #[test]
+    fn output_oriented() {
+        let game = Game::new();
+        assert_eq!(game.to_string_oriented(Colour::White), format!("{}", game));
+
+        // from Black's perspective, the black pieces are at the bottom with the king to the left of the queen
+        let lines: Vec<String> = game.to_string_oriented(Colour::Black).lines().map(String::from).collect();
+        assert_eq!(lines[1], "| wR  wKn wB  wK  wQ  wB  wKn wR |");
+        assert_eq!(lines[8], "| bR  bKn bB  bK  bQ  bB  bKn bR |");
+
+        // a8 is in the bottom right corner and h1 in the top left corner
+        let game = game_with_pieces(&[
+            ("a8", PieceType::Rook, Colour::Black),
+            ("h1", PieceType::Knight, Colour::White),
+        ]);
+        let lines: Vec<String> = game.to_string_oriented(Colour::Black).lines().map(String::from).collect();
+        assert_eq!(lines[1], "| wKn *   *   *   *   *   *   *  |");
+        assert_eq!(lines[8], "| *   *   *   *   *   *   *   bR |");
+
+        // flipping the board from Black's perspective and flipping it back gives the original layout
+        let game = Game::new_from_moves(&[("g1", "f3")]).unwrap();
+        let mut flipped = game.clone();
+        for i in 0..64 {
+            flipped.set_square(i - i % 8 + 7 - i % 8, game.board[i]);
+        }
+        assert_eq!(flipped.to_string_oriented(Colour::Black), game.to_string_oriented(Colour::White));
+        assert_ne!(game.to_string_oriented(Colour::Black), game.to_string_oriented(Colour::White));
+    }
+
+    // verify that the labeled board has row numbers and aligned column letters
+    #[test]
+    fn output_labeled() {
+        let game = Game::new();
+        let output = game.to_labeled_string();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "  |:------------------------------:|");
+        assert_eq!(lines[1], "1 | wR  wKn wB  wQ  wK  wB  wKn wR |");
+        assert_eq!(lines[8], "8 | bR  bKn bB  bQ  bK  bB  bKn bR |");
+        assert_eq!(lines[10], "    a   b   c   d   e   f   g   h");
+
+        // every column letter stands below the colour of the piece in its column
+        for (col, letter) in "abcdefgh".chars().enumerate() {
+            let offset = lines[10].find(letter).unwrap();
+            assert_eq!(offset, 4 + 4 * col);
+            assert_eq!(lines[1].chars().nth(offset), Some('w'));
+            assert_eq!(lines[8].chars().nth(offset), Some('b'));
+        }
+
+        // the regular output is unchanged
+        assert!(!format!("{}", game).contains('a'));
+    }
+
+    // verify that games reaching the same position by different move orders compare equal
+    #[test]
+    fn same_position_by_transposition() {
+        let game = Game::new_from_moves(&[("g1", "f3"), ("g8", "f6"), ("b1", "c3")]).unwrap();
+        let transposed = Game::new_from_moves(&[("b1", "c3"), ("g8", "f6"), ("g1", "f3")]).unwrap();
+        assert!(game.same_position(&transposed));
+        assert!(transposed.same_position(&game));
+
+        // the same board with the other colour to move is a different position
+        let mut other_colour = game.clone();
+        other_colour.active_colour = Colour::White;
+        assert!(!game.same_position(&other_colour));
+        assert!(!game.same_position(&Game::new()));
+    }
+
+    // verify that the Zobrist hash follows the position through moves and undos
+    #[test]
+    fn zobrist_hash_tracks_position() {
+        let mut game = Game::new();
+        let start_hash = game.zobrist_hash();
+        assert_eq!(start_hash, Game::new().zobrist_hash());
+
+        game.make_move("e2", "e4").unwrap();
+        assert_ne!(game.zobrist_hash(), start_hash);
+        game.undo_move().unwrap();
+        assert_eq!(game.zobrist_hash(), start_hash);
+
+        // the side to move is part of the hash
+        game.active_colour = Colour::Black;
+        assert_ne!(game.zobrist_hash(), start_hash);
+
+        // transpositions have the same hash
+        let game = Game::new_from_moves(&[("g1", "f3"), ("g8", "f6"), ("b1", "c3")]).unwrap();
+        let transposed = Game::new_from_moves(&[("b1", "c3"), ("g8", "f6"), ("g1", "f3")]).unwrap();
+        assert_eq!(game.zobrist_hash(), transposed.zobrist_hash());
+    }
+
+    // verify that every legal move of the active colour is listed
+    #[test]
+    fn all_legal_moves_from_start() {
+        let mut game = Game::new();
+        let moves = game.all_legal_moves();
+        assert_eq!(moves.len(), 20);
+        assert!(moves.contains(&(Position::parse_str("g1").unwrap(), Position::parse_str("f3").unwrap())));
+        assert!(moves.iter().all(|(from, _)| from.row <= 1));
+
+        game.make_move("e2", "e4").unwrap();
+        assert_eq!(game.all_legal_moves().len(), 20);
+        assert!(game.all_legal_moves().iter().all(|(from, _)| from.row >= 6));
+    }
+
+    // verify that a king can never move next to the enemy king, regardless of the ignored recursion order
+    #[test]
+    fn king_cannot_approach_enemy_king() {
+        let mut game = game_with_pieces(&[
+            ("h1", PieceType::King, Colour::White),
+            ("a2", PieceType::Pawn, Colour::White),
+            ("f2", PieceType::King, Colour::Black),
+            ("g3", PieceType::Bishop, Colour::Black),
+        ]);
+        game.update_game_state();
+        assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        // g1 and g2 are next to the black king and h2 is attacked by the bishop
+        let h1 = Position::parse_str("h1").unwrap();
+        assert!(game.get_possible_moves(h1, 0).is_empty());
+        for recursion_order in [0, 1, 2] {
+            let moves = game.get_possible_moves(h1, recursion_order);
+            assert!(!moves.contains(&Position::parse_str("g1").unwrap()));
+            assert!(!moves.contains(&Position::parse_str("g2").unwrap()));
+        }
+        assert!(game.make_move("h1", "g1").is_err());
+        assert!(game.make_move("h1", "g2").is_err());
+    }
+
+    // verify that a king defends the squares next to the enemy king, even though it cannot move there
+    #[test]
+    fn king_defends_squares_next_to_enemy_king() {
+        let game = Game::from_fen("8/8/4k3/3N4/8/4K3/8/7R w - - 0 1").unwrap();
+        let d5 = Position::parse_str("d5").unwrap();
+        let defending = game.moves_defending(d5);
+        for (from, to) in [("h1", "h5"), ("h1", "d1"), ("e3", "d4"), ("e3", "e4")] {
+            assert!(defending.contains(&(Position::parse_str(from).unwrap(), Position::parse_str(to).unwrap())), "{} {}", from, to);
+        }
+        assert_eq!(defending.len(), 4);
+
+        // the knight on d5 is defended by the king, but the king cannot capture on d5 next to the enemy king
+        let game = Game::from_fen("8/8/4k3/3N4/4K3/8/8/8 b - - 0 1").unwrap();
+        assert!(game.attacks(Position::parse_str("e4").unwrap(), d5));
+        assert!(!game.possible_moves(Position::parse_str("e6").unwrap()).contains(&d5));
+    }
 
-            If a move is found to be almost legal, a.k.a. moves to an empty piece or a piece of the opposite color, this function will check whether
-            the move puts the own king in check by calling is_check on the new board. This step is skipped if the recursion order is greater than
-            MAX_RECURSIONS.
+    // verify that pinned pieces may only move along the pin
+    #[test]
+    fn pinned_pieces_stay_on_pin() {
+        let square = |s: &str| Position::parse_str(s).unwrap();
 
-            There are comments guiding you through the if-clauses below if you need to read the code.
-        */
+        // a bishop pinned along a column cannot move at all
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e2", PieceType::Bishop, Colour::White),
+            ("e8", PieceType::Rook, Colour::Black),
+            ("a8", PieceType::King, Colour::Black),
+        ]);
+        assert!(game.get_possible_moves(square("e2"), 0).is_empty());
 
-        // Unwrapping is safe since it is not none.
-        let player_colour = self.board[old_pos.idx].unwrap().colour;
+        // a knight pinned along a diagonal cannot move at all
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("d2", PieceType::Knight, Colour::White),
+            ("a5", PieceType::Bishop, Colour::Black),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        assert!(game.get_possible_moves(square("d2"), 0).is_empty());
 
-        // Generate new position and check if it is in the board
-        let mut new_pos = old_pos.clone();
-        match new_pos.offset_self(offset) {
-            Err(_) => return (false, false), // If the new position is outside of the board, it is not valid and the engine should change direction.
-            _ => (),                         // continue
+        // a rook pinned along a column can move along it, up to and including capturing the pinning piece
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("e3", PieceType::Rook, Colour::White),
+            ("e8", PieceType::Rook, Colour::Black),
+            ("a8", PieceType::King, Colour::Black),
+        ]);
+        let moves = game.get_possible_moves(square("e3"), 0);
+        assert_eq!(moves.len(), 6);
+        assert!(moves.iter().all(|pos| pos.col == 4));
+        assert!(moves.contains(&square("e8")));
+    }
+
+    // verify that a game that has ended, whether won or drawn, is not put back into waiting on a promotion choice
+    #[test]
+    fn ended_game_does_not_wait_on_promotion() {
+        for state in [GameState::GameOver, GameState::Draw] {
+            let mut game = game_with_pieces(&[
+                ("e1", PieceType::King, Colour::White),
+                ("a8", PieceType::Pawn, Colour::White),
+                ("h1", PieceType::King, Colour::Black),
+            ]);
+            game.last_moved_to = Position::parse_str("a8").unwrap();
+            game.active_colour = Colour::Black;
+            game.state = state;
+            game.update_game_state();
+            assert_ne!(game.get_game_state(), GameState::WaitingOnPromotionChoice);
+        }
+    }
+
+    // verify that a piece pinned to its own king still gives check
+    #[test]
+    fn pinned_piece_gives_check() {
+        let mut game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("b2", PieceType::Bishop, Colour::White),
+            ("a3", PieceType::Pawn, Colour::White),
+            ("e5", PieceType::Rook, Colour::Black),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        game.update_game_state();
+        assert_eq!(game.get_game_state(), GameState::Check);
+        assert!(game.make_move("a3", "a4").is_err());
+        // capturing the rook with the bishop in turn checks the black king
+        assert_eq!(game.make_move("b2", "e5"), Ok(GameState::Check));
+    }
+
+    // verify the attack patterns of every piece type that can attack a square
+    #[test]
+    fn square_attack_patterns() {
+        let square = |s: &str| Position::parse_str(s).unwrap();
+        let attacked_by = |squares: &[(&str, PieceType, Colour)], target: &str, by: Colour| -> bool {
+            let mut pieces = vec![("a1", PieceType::King, Colour::White), ("h8", PieceType::King, Colour::Black)];
+            pieces.extend_from_slice(squares);
+            game_with_pieces(&pieces).is_square_attacked(square(target), by)
         };
 
-        // eprintln!("Trying to move {:?} from {:?} to {:?}", self.board[old_pos.idx], old_pos, new_pos); // DEBUG
+        // pawns attack diagonally forwards only
+        assert!(attacked_by(&[("c3", PieceType::Pawn, Colour::White)], "d4", Colour::White));
+        assert!(!attacked_by(&[("d3", PieceType::Pawn, Colour::White)], "d4", Colour::White));
+        assert!(!attacked_by(&[("c5", PieceType::Pawn, Colour::White)], "d4", Colour::White));
+        assert!(attacked_by(&[("c5", PieceType::Pawn, Colour::Black)], "d4", Colour::Black));
+        assert!(!attacked_by(&[("c3", PieceType::Pawn, Colour::Black)], "d4", Colour::Black));
 
-        // Clone into a new game to try the movement in that game
-        let mut game_after_movement = self.clone();
-        game_after_movement.board[new_pos.idx] = game_after_movement.board[old_pos.idx];
-        game_after_movement.board[old_pos.idx] = None;
-        game_after_movement.active_colour = Colour::opposite(game_after_movement.active_colour);
+        // knights jump, so they cannot be blocked
+        assert!(attacked_by(&[("e6", PieceType::Knight, Colour::White), ("e5", PieceType::Pawn, Colour::Black)], "d4", Colour::White));
+        assert!(!attacked_by(&[("e5", PieceType::Knight, Colour::White)], "d4", Colour::White));
 
-        // Check piece movement on the new board
-        let legal_move: bool;
-        let engine_should_continue: bool;
-        match self.board[new_pos.idx] {
-            // If there is no piece in the new slot, return false if the king is in check after movement or else true. Return true that the engine should keep checking the same direction.
-            None => {
-                engine_should_continue = true;
-                if recursion_order < Game::MAX_RECURSIONS {
-                    legal_move = !game_after_movement.is_in_check(player_colour, recursion_order);
-                } else {
-                    legal_move = true;
-                }
-                ();
-            }
-            // If there is a piece in the new slot, the engine should not keep checking the same direction...
-            Some(piece) => {
-                engine_should_continue = false;
-                // ... and the move is not legal if the piece is of the player's colour
-                if piece.colour == player_colour {
-                    legal_move = false;
-                }
-                // ... else the move is legal if the king is not in check after movement
-                else {
-                    if recursion_order < Game::MAX_RECURSIONS {
-                        legal_move = !game_after_movement.is_in_check(player_colour, recursion_order);
-                    } else {
-                        legal_move = true;
-                    }
-                }
-            }
-        }
+        // bishops attack along diagonals until blocked
+        assert!(attacked_by(&[("g7", PieceType::Bishop, Colour::Black)], "d4", Colour::Black));
+        assert!(!attacked_by(&[("g7", PieceType::Bishop, Colour::Black), ("f6", PieceType::Pawn, Colour::White)], "d4", Colour::Black));
+        assert!(!attacked_by(&[("d7", PieceType::Bishop, Colour::Black)], "d4", Colour::Black));
 
-        // eprintln!("Legal? {}. Engine should continue? {}", legal_move, engine_should_continue); // DEBUG
-        return (legal_move, engine_should_continue);
+        // rooks attack along rows and columns until blocked
+        assert!(attacked_by(&[("d8", PieceType::Rook, Colour::Black)], "d4", Colour::Black));
+        assert!(attacked_by(&[("h4", PieceType::Rook, Colour::Black)], "d4", Colour::Black));
+        assert!(!attacked_by(&[("d8", PieceType::Rook, Colour::Black), ("d6", PieceType::Knight, Colour::Black)], "d4", Colour::Black));
+        assert!(!attacked_by(&[("g7", PieceType::Rook, Colour::Black)], "d4", Colour::Black));
+
+        // queens attack like both rooks and bishops, but not like knights
+        assert!(attacked_by(&[("b2", PieceType::Queen, Colour::White)], "d4", Colour::White));
+        assert!(attacked_by(&[("d1", PieceType::Queen, Colour::White)], "d4", Colour::White));
+        assert!(!attacked_by(&[("e6", PieceType::Queen, Colour::White)], "d4", Colour::White));
+
+        // pieces only attack for their own colour
+        assert!(!attacked_by(&[("d8", PieceType::Rook, Colour::Black)], "d4", Colour::White));
     }
-}
 
-/// Implement print routine for Game.
-///
-/// Output example:
-/// |:------------------------------:|
-/// | wR  wKn wB  wK  wQ  wB  wKn wR |
-/// | wP  wP  wP  wP  wP  wP  wP  wP |
-/// | *   *   *   *   *   *   *   *  |
-/// | *   *   *   *   *   *   *   *  |
-/// | *   *   *   *   *   *   *   *  |
-/// | *   *   *   *   *   *   *   *  |
-/// | bP  bP  bP  bP  bP  bP  bP  bP |
-/// | bR  bKn bB  bK  bQ  bB  bKn bR |
-/// |:------------------------------:|
-///
-impl fmt::Display for Game {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // init output, the string we'll be coding our format to
-        let mut output = String::new();
+    // verify that possible_moves and get_possible_moves agree
+    #[test]
+    fn possible_moves_matches_get_possible_moves() {
+        let game = game_with_pieces(&[
+            ("e1", PieceType::King, Colour::White),
+            ("d4", PieceType::Knight, Colour::White),
+            ("e6", PieceType::Pawn, Colour::Black),
+            ("b3", PieceType::Pawn, Colour::White),
+            ("e8", PieceType::King, Colour::Black),
+        ]);
+        let d4 = Position::parse_str("d4").unwrap();
+        let moves = game.possible_moves(d4);
+        assert_eq!(moves.len(), 7);
+        assert_eq!(moves, game.get_possible_moves(d4, 0));
+    }
 
-        // start with the top row
-        output.push_str("|:------------------------------:|\n");
+    // verify that the fullmove number increases after every move by Black
+    #[test]
+    fn fullmove_number_counts_full_moves() {
+        let mut game = Game::new();
+        assert_eq!(game.fullmove_number(), 1);
+        game.make_move("e2", "e4").unwrap();
+        assert_eq!(game.fullmove_number(), 1);
+        assert_eq!(game.halfmove_clock(), 0);
+        game.make_move("g8", "f6").unwrap();
+        assert_eq!(game.fullmove_number(), 2);
+        assert_eq!(game.halfmove_clock(), 1);
+        assert!(game.to_fen().ends_with(" 1 2"));
 
-        // for every Option<piece> in board, print a representation. Also, for every beginning of a row i % 8 == 0 and end of a row i & 8 == 7 add corresponding slices.
-        for (i, piece) in self.board.iter().enumerate() {
-            if i % 8 == 0 {
-                output.push_str("|");
-            }
+        game.undo_move().unwrap();
+        assert_eq!(game.fullmove_number(), 1);
 
-            if piece.is_none() {
-                output.push_str(" *  "); // there is no piece here, add an asterisk
-            } else {
-                // from here, unwrapping is safe since the piece is not None
-                // add initial spacing
-                output.push_str(" ");
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 12 34").unwrap();
+        assert_eq!(game.fullmove_number(), 34);
+        assert_eq!(game.halfmove_clock(), 12);
+        assert!(Game::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 12 0").is_err());
+    }
 
-                // match dict for Colour representation
-                output.push_str(match piece.unwrap().colour {
-                    Colour::White => "w",
-                    Colour::Black => "b",
-                });
+    // verify the PGN export of a short mate and of a game that is still in progress
+    #[test]
+    fn pgn_export() {
+        let game = Game::new_from_moves(&[
+            ("e2", "e4"),
+            ("e7", "e5"),
+            ("f1", "c4"),
+            ("b8", "c6"),
+            ("d1", "h5"),
+            ("g8", "f6"),
+            ("h5", "f7"),
+        ])
+        .unwrap();
+        let pgn = game.to_pgn();
+        assert!(pgn.starts_with("[Result \"1-0\"]\n\n"));
+        assert!(pgn.ends_with("1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0\n"));
 
-                // match dict for PieceType representation
-                output.push_str(match piece.unwrap().piece_type {
-                    PieceType::King => "K ",
-                    PieceType::Queen => "Q ",
-                    PieceType::Bishop => "B ",
-                    PieceType::Knight => "Kn",
-                    PieceType::Rook => "R ",
-                    PieceType::Pawn => "P ",
-                });
-            }
+        // a game from a position with Black to move starts with the move number followed by an ellipsis
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 7").unwrap();
+        game.make_move("e8", "d8").unwrap();
+        game.make_move("a1", "a8").unwrap();
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[FEN \"4k3/8/8/8/8/8/8/R3K3 b - - 0 7\"]"));
+        assert!(pgn.ends_with("7... Kd8 8. Ra8+ *\n"));
+    }
 
-            if i % 8 == 7 {
-                output.push_str("|\n");
-            }
+    // verify that piece types and colours are parsed from names and letters in any case
+    #[test]
+    fn piece_type_and_colour_from_str() {
+        for s in ["queen", "Queen", "QUEEN", "q", "Q", " queen "] {
+            assert_eq!(s.parse::<PieceType>(), Ok(PieceType::Queen));
+        }
+        assert_eq!("n".parse::<PieceType>(), Ok(PieceType::Knight));
+        assert_eq!("Bishop".parse::<PieceType>(), Ok(PieceType::Bishop));
+        assert!("x".parse::<PieceType>().is_err());
+        for piece_type in [PieceType::King, PieceType::Queen, PieceType::Rook, PieceType::Knight, PieceType::Bishop, PieceType::Pawn] {
+            assert_eq!(piece_type.to_string().parse::<PieceType>(), Ok(piece_type));
         }
 
-        // end with the bottom row
-        output.push_str("|:------------------------------:|");
+        assert_eq!("W".parse::<Colour>(), Ok(Colour::White));
+        assert_eq!("black".parse::<Colour>(), Ok(Colour::Black));
+        assert!("red".parse::<Colour>().is_err());
+    }
 
-        write!(f, "{}", output)
+    // verify that pawns can be promoted by single letters, but never to kings or pawns
+    #[test]
+    fn set_promotion_parses_piece_type() {
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        game.make_move("a7", "a8").unwrap();
+        assert!(game.set_promotion(String::from("king")).is_err());
+        assert!(game.set_promotion(String::from("Pawn")).is_err());
+        assert!(game.set_promotion(String::from("dragon")).is_err());
+        assert_eq!(game.get_game_state(), GameState::WaitingOnPromotionChoice);
+        assert_eq!(game.set_promotion(String::from("N")), Ok(GameState::InProgress));
+        assert_eq!(game.get_board()[7 * 8].unwrap().piece_type, PieceType::Knight);
     }
-}
 
-impl fmt::Debug for Game {
-    // Make the formatter print game the same in debug mode as outside of debug mode.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self)
+    // verify that pawns can be promoted by piece type directly
+    #[test]
+    fn set_promotion_piece_promotes() {
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(game.set_promotion_piece(PieceType::Knight).is_err());
+
+        game.make_move("a7", "a8").unwrap();
+        assert!(game.set_promotion_piece(PieceType::King).is_err());
+        assert!(game.set_promotion_piece(PieceType::Pawn).is_err());
+        assert_eq!(game.set_promotion_piece(PieceType::Knight), Ok(GameState::InProgress));
+        assert_eq!(game.get_board()[7 * 8].unwrap().piece_type, PieceType::Knight);
     }
-}
 
-impl fmt::Display for Colour {
-    // Make the formatter print colours fancily outside of debug mode.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+    // verify that the game does not keep waiting on a promotion after the pawn has been promoted, also for consecutive promotions
+    #[test]
+    fn promotion_does_not_repeat() {
+        let mut game = Game::from_fen("6k1/P7/8/8/8/8/7p/K7 w - - 0 1").unwrap();
+
+        assert_eq!(game.make_move("a7", "a8"), Ok(GameState::WaitingOnPromotionChoice));
+        assert_eq!(game.set_promotion_piece(PieceType::Queen), Ok(GameState::Check));
+        assert_eq!(game.get_active_colour(), Colour::Black);
+        assert_eq!(game.make_move("g8", "g7"), Ok(GameState::InProgress));
+        assert_eq!(game.make_move("a1", "b2"), Ok(GameState::InProgress));
+
+        assert_eq!(game.make_move("h2", "h1"), Ok(GameState::WaitingOnPromotionChoice));
+        assert_eq!(game.set_promotion_piece(PieceType::Rook), Ok(GameState::InProgress));
+        assert_eq!(game.get_active_colour(), Colour::White);
+        assert_eq!(game.get_board()[7].unwrap(), Piece { piece_type: PieceType::Rook, colour: Colour::Black });
+
+        // both promoted pieces can move on as usual
+        assert_eq!(game.make_move("a8", "a1"), Ok(GameState::InProgress));
+        assert_eq!(game.make_move("h1", "a1"), Ok(GameState::InProgress));
     }
-}
 
-// --------------------------
-// ######### TESTS ##########
-// --------------------------
+    // verify that make_move promotes pawns directly when auto-promotion is set
+    #[test]
+    fn auto_promote_skips_promotion_choice() {
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(game.set_auto_promote(Some(PieceType::King)).is_err());
+        game.set_auto_promote(Some(PieceType::Queen)).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::Game;
-    use super::GameState;
-    use super::Position;
+        assert_eq!(game.make_move("a7", "a8"), Ok(GameState::Check));
+        assert_eq!(game.get_board()[7 * 8].unwrap().piece_type, PieceType::Queen);
+        assert_eq!(game.get_active_colour(), Colour::Black);
+        assert!(game.make_move("e8", "e7").is_ok());
 
-    // check test framework
+        // without auto-promotion, the game waits on a promotion choice again
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        game.set_auto_promote(Some(PieceType::Queen)).unwrap();
+        game.set_auto_promote(None).unwrap();
+        assert_eq!(game.make_move("a7", "a8"), Ok(GameState::WaitingOnPromotionChoice));
+    }
+
+    // verify that moves taking a pawn to the last row are always promotions
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn pawn_moves_to_last_row_are_promotions() {
+        let mut game = Game::from_fen("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let a7 = Position::parse_str("a7").unwrap();
+        let a8 = Position::parse_str("a8").unwrap();
+        let b8 = Position::parse_str("b8").unwrap();
+
+        assert!(game.is_promotion_move(a7, a8));
+        assert!(!game.is_promotion_move(Position::parse_str("e1").unwrap(), Position::parse_str("e2").unwrap()));
+
+        // both the step forwards and the capture are listed once per promotion piece
+        let moves = game.legal_moves_from(a7);
+        assert_eq!(moves.len(), 8);
+        assert!(moves.iter().all(|mv| mv.promotion.is_some()));
+        assert!(moves.contains(&Move::new_promotion(a7, b8, PieceType::Knight)));
+        assert!(game.legal_moves_from(Position::parse_str("e1").unwrap()).iter().all(|mv| mv.promotion.is_none()));
+
+        // a move without a promotion is rejected rather than leaving the pawn unpromoted
+        assert!(game.apply_move(Move::new(a7, a8)).is_err());
+        assert_eq!(game.get_board()[a7.idx].unwrap().piece_type, PieceType::Pawn);
+
+        // make_move takes the promotion choice separately, and no other move can be made before it is given
+        assert_eq!(game.make_move("a7", "a8"), Ok(GameState::WaitingOnPromotionChoice));
+        assert!(game.make_move("b8", "c6").is_err());
+        assert!(game.make_move("e1", "e2").is_err());
+        assert_eq!(game.set_promotion_piece(PieceType::Queen), Ok(GameState::InProgress));
+        // the knight is now pinned by the new queen
+        assert!(game.make_move("b8", "c6").is_err());
+        assert!(game.make_move("e8", "f7").is_ok());
     }
 
-    // example test
-    // check that game state is in progress after initialisation
+    // verify that positions can be set up on an empty board
     #[test]
-    fn game_in_progress_after_init() {
-        let game = Game::new();
+    fn set_up_position_on_empty_board() {
+        let mut game = Game::empty();
+        assert!(game.get_board().iter().all(|piece| piece.is_none()));
 
-        println!("{}", game);
+        // without a king, the pieces of a colour have no legal moves
+        let a1 = Position::parse_str("a1").unwrap();
+        game.place_piece(a1, Some(Piece::new(PieceType::Rook, Colour::White)));
+        assert!(game.possible_moves(a1).is_empty());
+        assert!(game.all_legal_moves().is_empty());
+        assert_eq!(game.mobility(Colour::White), 0);
+        assert!(!game.is_move_legal(a1, Position::parse_str("a8").unwrap()));
+        assert!(game.make_move("a1", "a8").is_err());
+        // nor is it in check, checkmated or stalemated, and the position is not legal
+        assert!(!game.is_in_check_now());
+        assert!(!game.is_checkmate());
+        assert!(!game.is_stalemate());
+        assert!(!game.is_legal_position());
+        game.set_active_colour(Colour::Black);
+        assert!(!game.is_in_check_now());
+        assert!(!game.is_checkmate());
+        assert!(!game.is_stalemate());
+        game.set_active_colour(Colour::White);
+        game.place_piece(a1, None);
 
+        let pieces = [
+            ("h8", PieceType::King, Colour::Black),
+            ("f6", PieceType::King, Colour::White),
+            ("g7", PieceType::Queen, Colour::White),
+        ];
+        for (pos, piece_type, colour) in pieces {
+            game.place_piece(Position::parse_str(pos).unwrap(), Some(Piece { piece_type, colour }));
+        }
         assert_eq!(game.get_game_state(), GameState::InProgress);
+
+        game.set_active_colour(Colour::Black);
+        assert_eq!(game.get_game_state(), GameState::GameOver);
+        assert_eq!(game.game_result(), Some(GameResult::Checkmate(Colour::White)));
+
+        // removing the queen leaves only the kings, which is a draw
+        game.place_piece(Position::parse_str("g7").unwrap(), None);
+        assert_eq!(game.get_game_state(), GameState::Draw);
+        assert_eq!(game.game_result(), Some(GameResult::DrawByRule(DrawRule::InsufficientMaterial)));
     }
 
+    // verify that setting up a position resets the halfmove clock and the fullmove number
     #[test]
-    fn position_checking_works() {
-        let possible_moves = vec![Position::new(0, 0).unwrap()];
-        let other_position = Position::new(0, 0).unwrap();
-        assert!(possible_moves
-            .iter() // Creates an iterable of positions.
-            .any(|pos| pos == &other_position)); // Checks if our position is equal to the list of possible moves. We use .any() since the objects may be different instances.
+    fn set_up_position_resets_move_counters() {
+        let mut game = Game::new_from_moves(&[("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")]).unwrap();
+        assert_eq!(game.halfmove_clock(), 4);
+        assert_eq!(game.fullmove_number(), 3);
+
+        game.place_piece(Position::parse_str("a1").unwrap(), None);
+        assert_eq!(game.halfmove_clock(), 0);
+        assert_eq!(game.fullmove_number(), 1);
+        assert!(game.to_fen().ends_with(" 0 1"));
+
+        game.make_move("g1", "f3").unwrap();
+        game.make_move("g8", "f6").unwrap();
+        game.set_active_colour(Colour::Black);
+        assert_eq!(game.halfmove_clock(), 0);
+        assert_eq!(game.fullmove_number(), 1);
     }
 
-    // check that game state is check after the queen attacks the king
+    // verify the material count and balance of the starting position and after removing a white rook
     #[test]
-    fn game_enters_check() {
+    fn material_balance_counts_pieces() {
         let mut game = Game::new();
-        let moves: Vec<&str> = "d2 d3
-        d7 d6
-        e1 b4
-        d6 d5
-        b4 d6"
-            .split_whitespace()
-            .collect();
+        assert_eq!(game.material_count(Colour::White), 39);
+        assert_eq!(game.material_count(Colour::Black), 39);
+        assert_eq!(game.material_balance(), 0);
 
-        for i in 0..(moves.len() / 2) {
-            let result = game.make_move(moves[2 * i], moves[2 * i + 1]);
-            assert!(result.is_ok());
+        game.place_piece(Position::parse_str("a1").unwrap(), None);
+        assert_eq!(game.material_count(Colour::White), 34);
+        assert_eq!(game.material_balance(), -5);
+    }
+
+    // verify that random moves are legal and that there are none in checkmate
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_move_is_legal() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1545);
+        let mut game = Game::new();
+        for _ in 0..20 {
+            let (from, to) = game.random_move(&mut rng).unwrap();
+            assert!(game.all_legal_moves().contains(&(from, to)));
+            game = game.clone_with_move(from, to);
+            if game.get_game_state() != GameState::InProgress && game.get_game_state() != GameState::Check {
+                break;
+            }
         }
 
-        assert_eq!(game.get_game_state(), GameState::Check);
+        let game = Game::new_from_moves(&[("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")]).unwrap();
+        assert_eq!(game.random_move(&mut rng), None);
     }
 
-    // check that the game state is checkmate after "skolmatt"
-    // due to the nature of the library, this also verifies that stalemate-checking will work
+    // verify that check, checkmate and stalemate are told apart
     #[test]
-    fn game_enters_checkmate() {
-        let mut game = Game::new();
-        let moves: Vec<&str> = "d2 d3
-        d7 d6
-        e1 c3
-        d6 d5
-        c1 f4
-        d5 d4
-        c3 c7"
-            .split_whitespace()
-            .collect();
+    fn check_queries_tell_endings_apart() {
+        let game = Game::new();
+        assert!(!game.is_in_check_now());
+        assert!(!game.is_checkmate());
+        assert!(!game.is_stalemate());
 
-        for i in 0..(moves.len() / 2) {
-            let result = game.make_move(moves[2 * i], moves[2 * i + 1]);
-            assert!(result.is_ok());
-        }
+        let mut game = game_with_pieces(&[
+            ("a1", PieceType::King, Colour::White),
+            ("c6", PieceType::Queen, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        game.make_move("c6", "g6").unwrap();
+        assert!(!game.is_in_check_now());
+        assert!(!game.is_checkmate());
+        assert!(game.is_stalemate());
 
-        eprintln!("{}", game);
-        assert_eq!(game.get_game_state(), GameState::GameOver);
+        game.undo_move().unwrap();
+        game.make_move("c6", "c8").unwrap();
+        assert!(game.is_in_check_now());
+        assert!(!game.is_checkmate());
+        assert!(!game.is_stalemate());
     }
 
-    // verify that the game enters the state waitingonpromotionchoice if a pawn should be promoted
+    // verify that the pieces of a type and colour are found
     #[test]
-    fn game_enters_waitingonpromitionchoice() {
-        let mut game = Game::new();
-        let moves: Vec<&str> = "e2 e3
-        d7 d6
-        e3 e4
-        d6 d5
-        e4 d5
-        d8 d7
-        d5 d6
-        d7 c6
-        d6 d7
-        c6 c5
-        d7 d8"
-            .split_whitespace()
-            .collect();
+    fn find_pieces_in_starting_position() {
+        let game = Game::new();
+        let pawns = game.find_pieces(PieceType::Pawn, Colour::White);
+        assert_eq!(pawns.len(), 8);
+        assert!(pawns.iter().all(|pos| pos.row == 1));
+        assert_eq!(game.find_pieces(PieceType::King, Colour::White), vec![Position::parse_str("e1").unwrap()]);
+        assert_eq!(
+            game.find_pieces(PieceType::Knight, Colour::Black),
+            vec![Position::parse_str("b8").unwrap(), Position::parse_str("g8").unwrap()]
+        );
+        assert!(Game::empty().find_pieces(PieceType::Queen, Colour::White).is_empty());
+    }
 
-        for i in 0..(moves.len() / 2) {
-            let result = game.make_move(moves[2 * i], moves[2 * i + 1]);
-            eprintln!(
-                "{} {}: {:?}",
-                moves[2 * i],
-                moves[2 * i + 1],
-                result.unwrap()
-            );
+    // verify that offsetting a position returns a new position and leaves the original untouched
+    #[test]
+    fn position_offset() {
+        let e4 = Position::parse_str("e4").unwrap();
+        assert_eq!(e4.offset((1, 0)), Ok(Position::parse_str("e5").unwrap()));
+        assert_eq!(e4.offset((-1, 2)), Ok(Position::parse_str("g3").unwrap()));
+        assert_eq!(e4, Position::parse_str("e4").unwrap());
+        assert!(e4.offset((5, 0)).is_err());
+        assert!(e4.offset((0, -5)).is_err());
+    }
+
+    // verify that the moves of a piece are split into captures and quiet moves
+    #[test]
+    fn possible_moves_categorized_splits_captures() {
+        let square = |s: &str| Position::parse_str(s).unwrap();
+        let game = game_with_pieces(&[
+            ("h1", PieceType::King, Colour::White),
+            ("c1", PieceType::Bishop, Colour::White),
+            ("f4", PieceType::Pawn, Colour::Black),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+
+        let (captures, quiet_moves) = game.possible_moves_categorized(square("c1"));
+        assert_eq!(captures, vec![square("f4")]);
+        assert_eq!(quiet_moves.len(), 4);
+        for pos in ["d2", "e3", "b2", "a3"] {
+            assert!(quiet_moves.contains(&square(pos)));
         }
+        assert_eq!(captures.len() + quiet_moves.len(), game.possible_moves(square("c1")).len());
+    }
 
-        assert_eq!(game.get_game_state(), GameState::WaitingOnPromotionChoice);
+    // verify the squares attacked by each colour in the starting position and that pawn pushes are not attacks
+    #[test]
+    fn attacked_squares_in_starting_position() {
+        let game = Game::new();
+        let attacked = game.attacked_squares(Colour::White);
+
+        // every square of the third row is attacked by the pawns or the knights, and none beyond it
+        assert!((0..8).all(|col| attacked.contains(&Position::new(2, col).unwrap())));
+        assert!(attacked.iter().all(|pos| pos.row <= 2));
+        // every square of the second row is defended by some piece behind it
+        assert!((0..8).all(|col| attacked.contains(&Position::new(1, col).unwrap())));
+        // on the first row, only the corners are not defended
+        assert!(!attacked.contains(&Position::parse_str("a1").unwrap()));
+        assert!(!attacked.contains(&Position::parse_str("h1").unwrap()));
+        assert_eq!(attacked.len(), 8 + 8 + 6);
+        assert_eq!(game.attacked_squares(Colour::Black).len(), 8 + 8 + 6);
+
+        // pawns attack diagonally, not the square in front of them
+        let game = game_with_pieces(&[
+            ("a1", PieceType::King, Colour::White),
+            ("e4", PieceType::Pawn, Colour::White),
+            ("h8", PieceType::King, Colour::Black),
+        ]);
+        let attacked = game.attacked_squares(Colour::White);
+        assert!(attacked.contains(&Position::parse_str("d5").unwrap()));
+        assert!(attacked.contains(&Position::parse_str("f5").unwrap()));
+        assert!(!attacked.contains(&Position::parse_str("e5").unwrap()));
+        assert_eq!(attacked.len(), 3 + 2);
     }
 
-    // verify that a pawn can be promoted
+    // verify that the pieces that can move to a square are found
     #[test]
-    fn game_promotes_correctly() {
+    fn movers_to_central_square() {
+        let square = |s: &str| Position::parse_str(s).unwrap();
+        let game = Game::new_from_moves(&[("e2", "e4"), ("e7", "e5"), ("g1", "f3"), ("b8", "c6")]).unwrap();
+
+        // the d-pawn and the knight on f3 can reach d4, but neither the e-pawn nor the other knight can
+        assert_eq!(game.movers_to(square("d4"), Colour::White), vec![square("d2"), square("f3")]);
+        // the black knight can reach d4 too, even though it is not Black's turn, while the e-pawn is blocked
+        assert_eq!(game.movers_to(square("d4"), Colour::Black), vec![square("c6")]);
+        // the knight can capture on e5, while the blocked e-pawn can't move there
+        assert_eq!(game.movers_to(square("e5"), Colour::White), vec![square("f3")]);
+    }
+
+    // verify that a timed game is lost by the colour that runs out of time, and that moves in time are charged and given the increment
+    #[test]
+    fn clock_flags_timeout() {
         let mut game = Game::new();
-        let moves: Vec<&str> = "e2 e3
-        d7 d6
-        e3 e4
-        d6 d5
-        e4 d5
-        d8 d7
-        d5 d6
-        d7 c6
-        d6 d7
-        c6 c5
-        d7 d8"
-            .split_whitespace()
-            .collect();
+        game.set_clock(Some(GameClock::new(Duration::from_secs(60), Duration::from_secs(1))));
+        assert_eq!(game.make_move("e2", "e4"), Ok(GameState::InProgress));
+        let white_remaining = game.get_clock().unwrap().time_remaining(Colour::White);
+        assert!(white_remaining > Duration::from_secs(60) && white_remaining <= Duration::from_secs(61));
+        // it is Black's turn, so Black's time is running, but it may not have measurably passed yet
+        assert!(game.get_clock().unwrap().time_remaining(Colour::Black) <= Duration::from_secs(60));
 
-        for i in 0..(moves.len() / 2) {
-            let result = game.make_move(moves[2 * i], moves[2 * i + 1]);
-            eprintln!(
-                "{} {}: {:?}",
-                moves[2 * i],
-                moves[2 * i + 1],
-                result.unwrap()
-            );
+        let mut game = Game::new();
+        game.set_clock(Some(GameClock::new(Duration::from_millis(5), Duration::ZERO)));
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(game.get_clock().unwrap().time_remaining(Colour::White), Duration::ZERO);
+        assert_eq!(game.make_move("e2", "e4"), Ok(GameState::GameOver));
+        assert_eq!(game.game_result(), Some(GameResult::Timeout(Colour::Black)));
+        // the move was not made
+        assert!(game.get_board()[Position::parse_str("e2").unwrap().idx].is_some());
+        assert!(game.make_move("d2", "d4").is_err());
+    }
+
+    // verify that moves are parsed from and formatted to UCI notation, and made by make_move_uci
+    #[test]
+    fn uci_moves() {
+        let e2 = Position::parse_str("e2").unwrap();
+        let e4 = Position::parse_str("e4").unwrap();
+        assert_eq!(Move::parse_uci("e2e4"), Ok(Move::new(e2, e4)));
+        assert_eq!(Move::parse_uci(" E2E4 "), Ok(Move::new(e2, e4)));
+        assert_eq!(Move::new(e2, e4).to_uci(), "e2e4");
+        for invalid in ["e2e", "e2e4e5", "e2e9", "e7e8k", "e2-e4"] {
+            assert!(Move::parse_uci(invalid).is_err(), "{} should not parse", invalid);
         }
 
-        assert_eq!(game.get_game_state(), GameState::WaitingOnPromotionChoice);
-        assert!(game.set_promotion(String::from("queen")).is_ok());
-        assert_eq!(game.get_game_state(), GameState::InProgress);
-        eprintln!("{}", game);
+        let mut game = Game::new();
+        assert_eq!(game.make_move_uci("e2e4"), Ok(GameState::InProgress));
+        assert_eq!(game.get_board()[e4.idx].unwrap().piece_type, PieceType::Pawn);
+        assert!(game.make_move_uci("e2e4").is_err());
+
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(game.make_move_uci("a7a8").is_err());
+        assert_eq!(game.make_move_uci("a7a8r"), Ok(GameState::Check));
+        assert_eq!(game.get_board()[7 * 8].unwrap().piece_type, PieceType::Rook);
     }
 
-    // verify that the output is accurate
+    // verify that make_move_str accepts moves with and without whitespace between the positions
     #[test]
-    fn output_accurate() {
-        let game = Game::new();
+    fn make_move_str_spaced_and_unspaced() {
+        let mut game = Game::new();
+        assert_eq!(game.make_move_str("e2 e4"), Ok(GameState::InProgress));
+        assert_eq!(game.make_move_str("e7e5"), Ok(GameState::InProgress));
+        assert_eq!(game.make_move_str("  G1   F3 "), Ok(GameState::InProgress));
+        assert!(game.get_board()[Position::parse_str("f3").unwrap().idx].is_some());
+        for invalid in ["", "e7", "e7 e5 e4", "e7-e5", "b8 b6"] {
+            assert!(game.make_move_str(invalid).is_err(), "{} should not be made", invalid);
+        }
+        assert_eq!(game.get_active_colour(), Colour::Black);
 
-        assert_eq!(
-            format!("{}", game),
-            "|:------------------------------:|
-| wR  wKn wB  wK  wQ  wB  wKn wR |
-| wP  wP  wP  wP  wP  wP  wP  wP |
-| *   *   *   *   *   *   *   *  |
-| *   *   *   *   *   *   *   *  |
-| *   *   *   *   *   *   *   *  |
-| *   *   *   *   *   *   *   *  |
-| bP  bP  bP  bP  bP  bP  bP  bP |
-| bR  bKn bB  bK  bQ  bB  bKn bR |
-|:------------------------------:|"
-        );
+        // the promotion piece may follow the destination
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.make_move_str("a7 a8 n"), Ok(GameState::InProgress));
+        assert_eq!(game.get_board()[7 * 8].unwrap().piece_type, PieceType::Knight);
+
+        let mut game = Game::from_fen("4k3/P6p/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.make_move_str("a7a8"), Ok(GameState::WaitingOnPromotionChoice));
     }
 }
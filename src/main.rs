@@ -1,4 +1,4 @@
-mod lib;
+use chess_template as lib;
 
 fn main() {
     let mut game = lib::Game::new();
@@ -23,17 +23,18 @@ fn main() {
             .expect("Invalid iostream.")
             .expect("Error."); // expect errors
         let input: Vec<&str> = input_tmp
-            .trim() // remove whitespaces
-            .split(" ")
+            .split_whitespace() // split on runs of whitespace, ignoring leading and trailing whitespace
             .collect();
 
         // provide state and colour reading to user
-        if input[0] == "state" {
+        if input.is_empty() {
+            println!("Invalid input. Please try again!");
+        } else if input[0] == "state" {
             println!("{:?}", game.get_game_state());
         } else if input[0] == "colour" {
             println!("{:?}", game.get_active_colour());
         } else if input[0] == "gm" {
-            println!("{:?}", game.get_possible_moves(lib::Position::parse_str(input[1]).unwrap(), 0));
+            println!("{:?}", game.possible_moves(lib::Position::parse_str(input[1]).unwrap()));
         } else if input[0] == "piece" {
             println!("{:?}", game.get_board()[lib::Position::parse_str(input[1]).unwrap().idx]);
         } else if input.len() == 2 {
@@ -56,10 +57,12 @@ fn main() {
                 .expect("Invalid iostream.")
                 .expect("Error."); // expect errors
             let input: Vec<&str> = input_tmp
-                .trim() // remove whitespaces
-                .split(" ")
+                .split_whitespace() // split on runs of whitespace, ignoring leading and trailing whitespace
                 .collect();
 
+            if input.is_empty() {
+                continue;
+            }
             match game.set_promotion(String::from(input[0])) {
                 Ok(_) => println!("Successfully promoted the piece!"),
                 Err(msg) => println!("Error received:\n{}\nPlease try again!", msg),
@@ -0,0 +1,62 @@
+use chess_template::{Game, Move};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the UCI engine with `input` on stdin and returns what it printed on stdout.
+fn run_engine(input: &str) -> String {
+    let mut engine = Command::new(env!("CARGO_BIN_EXE_uci"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    engine
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = engine.wait_with_output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// verify that the engine answers the handshake
+#[test]
+fn uci_handshake() {
+    let output = run_engine("uci\nisready\nquit\n");
+    assert!(output.lines().any(|line| line == "uciok"));
+    assert!(output.lines().any(|line| line == "readyok"));
+}
+
+// verify that the engine replies with a legal best move from the starting position
+#[test]
+fn uci_bestmove_is_legal() {
+    let output = run_engine("position startpos\ngo depth 1\nquit\n");
+    let bestmove = output
+        .lines()
+        .find_map(|line| line.strip_prefix("bestmove "))
+        .unwrap();
+    let mv = Move::parse_uci(bestmove).unwrap();
+    assert!(Game::new().all_legal_moves().contains(&(mv.from, mv.to)));
+}
+
+// verify that the engine follows the moves given after the position and finds a mate in one
+#[test]
+fn uci_bestmove_after_moves() {
+    let output =
+        run_engine("position startpos moves e2e4 e7e5 f1c4 b8c6 d1h5 g8f6\ngo depth 1\nquit\n");
+    assert!(output.lines().any(|line| line == "bestmove h5f7"));
+
+    let output = run_engine("position fen 7k/8/8/8/8/8/8/KQ6 b - - 0 1\ngo\nquit\n");
+    let bestmove = output
+        .lines()
+        .find_map(|line| line.strip_prefix("bestmove "))
+        .unwrap();
+    assert!(bestmove.starts_with("h8"));
+}
+
+// verify that the engine has no move to give once the game has ended
+#[test]
+fn uci_bestmove_after_game_end() {
+    let output = run_engine("position fen 7k/8/8/8/8/8/8/K7 w - - 0 1\ngo depth 1\nquit\n");
+    assert!(output.lines().any(|line| line == "bestmove 0000"));
+}